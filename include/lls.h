@@ -0,0 +1,36 @@
+/* C interface of longestlyndonsubseq, built with
+ *   cargo rustc --release --lib --no-default-features --features ffi --crate-type cdylib
+ */
+#ifndef LLS_H
+#define LLS_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define LLS_OK 0
+#define LLS_NULL_POINTER -1
+#define LLS_INTERNAL_ERROR -2
+
+/* a longest Lyndon subsequence, owned by the library until passed to lls_free */
+typedef struct lls_result {
+	size_t length;        /* length of the subsequence */
+	size_t* positions;    /* the `length` text positions of the subsequence */
+	uint8_t* subsequence; /* the `length` characters of the subsequence */
+} lls_result;
+
+/* computes a longest Lyndon subsequence of text[0..length) and stores it in result;
+ * returns LLS_OK on success, after which result has to be released with lls_free */
+int lls_compute(const uint8_t* text, size_t length, lls_result* result);
+
+/* releases the memory of a result filled by lls_compute */
+void lls_free(lls_result* result);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* LLS_H */
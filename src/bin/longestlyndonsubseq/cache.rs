@@ -0,0 +1,116 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+
+use crate::{Args, Solution};
+
+/// the results of texts stored in a directory, keyed by the hash of the options that affect them and of the text
+pub struct Cache {
+    dir: PathBuf,
+    /// the options that affect the results, see [`options`]
+    options: String,
+}
+
+/// a description of the options in `args` that change the results of a text, and of the version of the tool
+///
+/// The parallel search may find another of several longest subsequences, so the number of threads is part of it.
+fn options(args: &Args) -> String {
+    let alphabet = args.alphabet.as_ref().map(|alphabet| alphabet.chars());
+    format!(
+        "{} unicode={} graphemes={} collation={:?} tokens={} ignore-case={} include={:?} exclude={:?} strip-whitespace={} alphabet={:?} unlisted={:?} reverse={} length-only={} top-k={:?} threads={}",
+        env!("CARGO_PKG_VERSION"),
+        args.unicode,
+        args.graphemes,
+        args.collation,
+        args.tokens,
+        args.ignore_case,
+        args.include_chars,
+        args.exclude_chars,
+        args.strip_whitespace,
+        alphabet,
+        args.unlisted,
+        args.reverse_alphabet,
+        args.length_only,
+        args.top_k,
+        args.threads,
+    )
+}
+
+impl Cache {
+    /// the cache in `dir` for the options in `args`, creating the directory if needed
+    pub fn new(dir: &Path, args: &Args) -> Result<Self> {
+        fs::create_dir_all(dir).with_context(|| format!("cannot create {}", dir.display()))?;
+        Ok(Cache {
+            dir: dir.to_owned(),
+            options: options(args),
+        })
+    }
+
+    fn path(&self, text: &[u8]) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(self.options.as_bytes());
+        hasher.update([0]);
+        hasher.update(text);
+        let key: String = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        self.dir.join(format!("{key}.json"))
+    }
+
+    /// the stored results of `text`, if any; unreadable entries are ignored since they are computed again
+    pub fn get(&self, text: &[u8]) -> Option<Vec<Solution>> {
+        let path = self.path(text);
+        let entry = match fs::read(&path) {
+            Ok(entry) => entry,
+            Err(error) if error.kind() == ErrorKind::NotFound => return None,
+            Err(error) => {
+                log::warn!("cannot read {}: {error}", path.display());
+                return None;
+            }
+        };
+        serde_json::from_slice(&entry)
+            .map_err(|error| log::warn!("ignoring {}: {error}", path.display()))
+            .ok()
+    }
+
+    /// stores the `results` of `text`
+    pub fn put(&self, text: &[u8], results: &[Solution]) -> Result<()> {
+        let path = self.path(text);
+        // written under another name first, such that concurrent runs never read a partial entry
+        let partial = path.with_extension(format!("{}.partial", std::process::id()));
+        fs::write(&partial, serde_json::to_vec(results)?)
+            .with_context(|| format!("cannot write {}", partial.display()))?;
+        fs::rename(&partial, &path).with_context(|| format!("cannot write {}", path.display()))?;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_cache() {
+    use clap::Parser;
+
+    let dir = std::env::temp_dir().join(format!("lls-cache-{}", std::process::id()));
+    let args = crate::Cli::parse_from(["lls", "--alphabet", "ba"]).args;
+    let cache = Cache::new(&dir, &args).unwrap();
+    assert!(cache.get(b"ab").is_none());
+    cache.put(b"ab", &[Solution::Length(1)]).unwrap();
+    assert!(matches!(
+        cache.get(b"ab").as_deref(),
+        Some([Solution::Length(1)])
+    ));
+    assert!(cache.get(b"abc").is_none());
+    // another order gives other results
+    let args = crate::Cli::parse_from(["lls", "--alphabet", "ab"]).args;
+    assert!(Cache::new(&dir, &args).unwrap().get(b"ab").is_none());
+    // the parallel search may find another subsequence
+    let args = crate::Cli::parse_from(["lls", "--alphabet", "ba", "--threads", "2"]).args;
+    assert!(Cache::new(&dir, &args).unwrap().get(b"ab").is_none());
+    fs::write(cache.path(b"ab"), "corrupt").unwrap();
+    assert!(cache.get(b"ab").is_none());
+    fs::remove_dir_all(&dir).unwrap();
+}
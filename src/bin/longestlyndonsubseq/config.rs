@@ -0,0 +1,147 @@
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use toml::Value;
+
+/// the name of the configuration file searched in the current directory and the configuration directory
+const CONFIG_FILE: &str = "lls.toml";
+
+/// the directory `lls` in `$XDG_CONFIG_HOME` or `~/.config`
+pub fn config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("lls"))
+}
+
+/// the configuration files used without `--config`, in the order they are searched
+fn default_paths() -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from(CONFIG_FILE)];
+    paths.extend(config_dir().map(|dir| dir.join(CONFIG_FILE)));
+    paths
+}
+
+/// the value of `--config` in `args`
+fn config_argument(args: &[OsString]) -> Option<PathBuf> {
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        let arg = arg.to_string_lossy();
+        if arg == "--config" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(path.into());
+        }
+    }
+    None
+}
+
+/// where the options of the configuration file go in `args`: after the program name, or after one of the `commands`
+///
+/// Other subcommands do not take the options.
+fn insertion_point(args: &[OsString], commands: &[&str]) -> Option<usize> {
+    match args.get(1).map(|arg| arg.to_string_lossy()) {
+        Some(arg) if commands.contains(&&*arg) => Some(2),
+        Some(arg) if !arg.starts_with('-') => None,
+        _ => Some(1),
+    }
+}
+
+/// `args` with the options of the configuration file inserted before the command line options, such that those override them
+///
+/// The keys of the file are the long options without the leading dashes, e.g., `alphabet = "ACGT"` or `ignore-case = true`.
+pub fn with_config(args: Vec<OsString>, commands: &[&str]) -> Result<Vec<OsString>> {
+    let at = match insertion_point(&args, commands) {
+        Some(at) => at,
+        None => return Ok(args),
+    };
+    let path = match config_argument(&args) {
+        Some(path) => path,
+        None => match default_paths().into_iter().find(|path| path.is_file()) {
+            Some(path) => path,
+            None => return Ok(args),
+        },
+    };
+    let config = fs::read_to_string(&path)
+        .with_context(|| format!("cannot read the configuration {}", path.display()))?;
+    let options =
+        parse(&config).with_context(|| format!("invalid configuration {}", path.display()))?;
+    let mut args = args.into_iter();
+    let command: Vec<OsString> = args.by_ref().take(at).collect();
+    Ok(command.into_iter().chain(options).chain(args).collect())
+}
+
+/// the command line options set by the TOML document `config`
+fn parse(config: &str) -> Result<Vec<OsString>> {
+    let table: toml::value::Table = toml::from_str(config)?;
+    let mut options = Vec::new();
+    for (key, value) in table {
+        let option = format!("--{}", key.replace('_', "-"));
+        let scalar = |value: &Value| {
+            Ok(match value {
+                Value::String(value) => value.clone(),
+                Value::Integer(value) => value.to_string(),
+                Value::Float(value) => value.to_string(),
+                _ => bail!("unsupported value of {key}: {value}"),
+            })
+        };
+        match &value {
+            Value::Boolean(true) => options.push(option.into()),
+            Value::Boolean(false) => {}
+            Value::Array(values) => {
+                options.push(option.into());
+                for value in values {
+                    options.push(scalar(value)?.into());
+                }
+            }
+            value => options.push(format!("{option}={}", scalar(value)?).into()),
+        }
+    }
+    Ok(options)
+}
+
+#[test]
+fn test_parse() {
+    let config = "alphabet = \"-ACGT\"\nignore_case = true\nunicode = false\nprefix = 100\ninclude = [\"*.fa\", \"*.fq\"]\n";
+    let options: Vec<String> = parse(config)
+        .unwrap()
+        .into_iter()
+        .map(|option| option.into_string().unwrap())
+        .collect();
+    assert_eq!(
+        options,
+        [
+            "--alphabet=-ACGT",
+            "--ignore-case",
+            "--include",
+            "*.fa",
+            "*.fq",
+            "--prefix=100"
+        ]
+    );
+    assert!(parse("format = { kind = \"fasta\" }").is_err());
+    let args = |args: &[&str]| args.iter().map(OsString::from).collect::<Vec<_>>();
+    assert_eq!(
+        config_argument(&args(&["lls", "-t", "abc", "--config", "a.toml"])),
+        Some("a.toml".into())
+    );
+    assert_eq!(
+        config_argument(&args(&["lls", "--config=b.toml"])),
+        Some("b.toml".into())
+    );
+    assert_eq!(config_argument(&args(&["lls", "-t", "--config"])), None);
+    let commands = &["check"];
+    assert_eq!(
+        insertion_point(&args(&["lls", "-t", "abc"]), commands),
+        Some(1)
+    );
+    assert_eq!(
+        insertion_point(&args(&["lls", "check", "-t", "abc"]), commands),
+        Some(2)
+    );
+    assert_eq!(insertion_point(&args(&["lls", "generate"]), commands), None);
+    assert_eq!(insertion_point(&args(&["lls"]), commands), Some(1));
+}
@@ -0,0 +1,140 @@
+use std::io::{self, Write};
+
+use anyhow::Result;
+use clap::ArgEnum;
+
+/// how the symbols of a generated text are drawn
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Distribution {
+    /// each symbol independently with the same probability
+    Uniform,
+    /// each symbol depending on its predecessor, with random transition probabilities
+    Markov,
+    /// each symbol independently, the i-th one with a probability proportional to 1/i^exponent
+    Zipf,
+}
+
+/// write a reproducible random text to the standard output
+#[derive(clap::Args, Debug)]
+pub struct GenerateArgs {
+    /// number of symbols to generate
+    #[clap(short = 'n', long)]
+    length: u64,
+
+    /// number of distinct symbols, taken from `a` to `z` for at most 26, otherwise the bytes from 0 on
+    #[clap(short = 'k', long, default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..=256))]
+    alphabet_size: u16,
+
+    /// seed of the random number generator, equal seeds give equal texts
+    #[clap(short, long, default_value_t = 0)]
+    seed: u64,
+
+    /// how the symbols are drawn
+    #[clap(short, long, arg_enum, default_value_t = Distribution::Uniform)]
+    distribution: Distribution,
+
+    /// exponent of the Zipfian distribution
+    #[clap(long, default_value_t = 1.0)]
+    exponent: f64,
+}
+
+/// splitmix64, small and fixed so that a seed gives the same text on every platform and version
+struct Random(u64);
+
+impl Random {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// uniform in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// prefix sums of `weights`, for drawing an index with `sample`
+fn cumulative(weights: impl Iterator<Item = f64>) -> Vec<f64> {
+    weights
+        .scan(0.0, |sum, weight| {
+            *sum += weight;
+            Some(*sum)
+        })
+        .collect()
+}
+
+fn sample(random: &mut Random, cumulative: &[f64]) -> usize {
+    let total = cumulative[cumulative.len() - 1];
+    let target = random.next_f64() * total;
+    cumulative
+        .partition_point(|&sum| sum <= target)
+        .min(cumulative.len() - 1)
+}
+
+/// the random text described by `args`
+pub fn generate(args: &GenerateArgs) -> impl Iterator<Item = u8> {
+    let k = args.alphabet_size as usize;
+    let symbols: Vec<u8> = match k {
+        1..=26 => (b'a'..).take(k).collect(),
+        _ => (0..=u8::MAX).take(k).collect(),
+    };
+    let mut random = Random(args.seed);
+    let rows: Vec<Vec<f64>> = match args.distribution {
+        Distribution::Uniform => vec![cumulative((0..k).map(|_| 1.0))],
+        Distribution::Zipf => vec![cumulative((1..=k).map(|i| (i as f64).powf(-args.exponent)))],
+        Distribution::Markov => (0..k)
+            .map(|_| cumulative((0..k).map(|_| random.next_f64())))
+            .collect(),
+    };
+    let markov = args.distribution == Distribution::Markov;
+    let mut previous = 0;
+    (0..args.length).map(move |_| {
+        let row = &rows[if markov { previous } else { 0 }];
+        previous = sample(&mut random, row);
+        symbols[previous]
+    })
+}
+
+pub fn run(args: &GenerateArgs) -> Result<()> {
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    let mut buffer = Vec::with_capacity(1 << 16);
+    for symbol in generate(args) {
+        buffer.push(symbol);
+        if buffer.len() == buffer.capacity() {
+            out.write_all(&buffer)?;
+            buffer.clear();
+        }
+    }
+    out.write_all(&buffer)?;
+    out.flush()?;
+    Ok(())
+}
+
+#[test]
+fn test_generate() {
+    let args = |seed, distribution| GenerateArgs {
+        length: 1000,
+        alphabet_size: 3,
+        seed,
+        distribution,
+        exponent: 1.0,
+    };
+    for distribution in [
+        Distribution::Uniform,
+        Distribution::Markov,
+        Distribution::Zipf,
+    ] {
+        let text: Vec<u8> = generate(&args(7, distribution)).collect();
+        assert_eq!(text.len(), 1000);
+        assert!(text.iter().all(|c| (b'a'..=b'c').contains(c)));
+        assert!((b'a'..=b'c').all(|c| text.contains(&c)));
+        assert_eq!(text, generate(&args(7, distribution)).collect::<Vec<_>>());
+        assert_ne!(text, generate(&args(8, distribution)).collect::<Vec<_>>());
+    }
+    let zipf: Vec<u8> = generate(&args(7, Distribution::Zipf)).collect();
+    let count = |c| zipf.iter().filter(|&&d| d == c).count();
+    assert!(count(b'a') > count(b'b') && count(b'b') > count(b'c'));
+}
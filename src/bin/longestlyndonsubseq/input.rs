@@ -0,0 +1,864 @@
+use std::fs;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+use std::ops::{Deref, Range};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use flate2::bufread::MultiGzDecoder;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use memmap2::Mmap;
+use walkdir::WalkDir;
+
+use longestlyndonsubseq::{file_range2byte_vector, from_reader};
+
+use crate::sequence::{Fasta, Fastq, SequenceRecord, TwoBit};
+
+/// where a text comes from
+#[derive(Debug, Clone)]
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+    Inline(String),
+    /// an `http://` or `https://` URL
+    Url(String),
+    /// the texts of several sources one after another, with the separator between two of them
+    Concat(Vec<Source>, Option<u8>),
+}
+
+impl Source {
+    /// the files read for this source
+    pub fn paths(&self) -> Vec<&Path> {
+        match self {
+            Source::File(path) => vec![path],
+            Source::Concat(sources, _) => sources.iter().flat_map(Source::paths).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// the size in bytes of the files read for this source, as stored, or `None` for other sources
+    pub fn size(&self) -> Option<u64> {
+        match self {
+            Source::File(path) => fs::metadata(path).ok().map(|metadata| metadata.len()),
+            Source::Concat(sources, _) => sources.iter().map(Source::size).sum(),
+            _ => None,
+        }
+    }
+
+    /// the source given on the command line: `-` is the standard input
+    pub fn from_argument(argument: &str) -> Self {
+        match argument {
+            "-" => Source::Stdin,
+            url if url.starts_with("http://") || url.starts_with("https://") => {
+                Source::Url(url.to_owned())
+            }
+            path => Source::File(path.into()),
+        }
+    }
+
+    /// how results of this source are labeled
+    pub fn label(&self) -> String {
+        match self {
+            Source::Stdin => "-".to_owned(),
+            Source::File(path) => path.display().to_string(),
+            Source::Inline(_) => "text".to_owned(),
+            Source::Url(url) => url.clone(),
+            Source::Concat(sources, _) => {
+                let labels: Vec<String> = sources.iter().map(Source::label).collect();
+                labels.join("+")
+            }
+        }
+    }
+
+    /// opens the source for streaming its bytes
+    fn open(&self) -> Result<Box<dyn BufRead>> {
+        Ok(match self {
+            Source::Stdin => Box::new(io::stdin().lock()),
+            Source::File(path) => Box::new(BufReader::new(
+                fs::File::open(path).with_context(|| format!("cannot read {}", path.display()))?,
+            )),
+            Source::Inline(text) => Box::new(Cursor::new(text.clone().into_bytes())),
+            // the body is streamed, so reading only a prefix stops the download early
+            #[cfg(feature = "http")]
+            Source::Url(url) => Box::new(BufReader::new(
+                ureq::get(url)
+                    .call()
+                    .with_context(|| format!("cannot fetch {url}"))?
+                    .into_reader(),
+            )),
+            #[cfg(not(feature = "http"))]
+            Source::Url(url) => bail!("cannot fetch {url}, rebuild with the feature `http`"),
+            Source::Concat(..) => unreachable!("concatenations are opened by InputOptions::open"),
+        })
+    }
+}
+
+/// a text to process, with the label of its result
+#[derive(Debug)]
+pub struct Record {
+    /// the label of the source
+    pub input: String,
+    /// the record within the source, e.g., a line number or a sequence identifier
+    pub name: Option<String>,
+    pub text: Text,
+    /// the positions of the text in the (decompressed) bytes of the source, if they are known
+    pub range: Option<Range<u64>>,
+    /// the number of characters of the (decoded) source or sequence before the text, e.g., skipped by the offset, which the written positions count
+    pub start: usize,
+}
+
+impl Record {
+    /// `input:name`, or just `input` for a whole source
+    pub fn label(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{}:{}", self.input, name),
+            None => self.input.clone(),
+        }
+    }
+}
+
+/// the bytes of a text, either read into memory or mapped from its file
+#[derive(Debug)]
+pub enum Text {
+    Read(Vec<u8>),
+    Mapped(Mmap, Range<usize>),
+}
+
+impl Deref for Text {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Text::Read(text) => text,
+            Text::Mapped(map, range) => &map[range.clone()],
+        }
+    }
+}
+
+impl From<Vec<u8>> for Text {
+    fn from(text: Vec<u8>) -> Self {
+        Text::Read(text)
+    }
+}
+
+/// parses a record delimiter: `nul`, `newline`, `tab`, a hexadecimal byte like `0x1e`, or a single ASCII character
+pub fn parse_delimiter(delimiter: &str) -> Result<u8> {
+    Ok(match delimiter {
+        "nul" => b'\0',
+        "newline" => b'\n',
+        "tab" => b'\t',
+        _ => match delimiter.strip_prefix("0x") {
+            Some(hex) => u8::from_str_radix(hex, 16)
+                .with_context(|| format!("invalid hexadecimal byte {delimiter:?}"))?,
+            None if delimiter.len() == 1 && delimiter.is_ascii() => delimiter.as_bytes()[0],
+            None => bail!("expected nul, newline, tab, a byte like 0x1e, or a single character"),
+        },
+    })
+}
+
+/// which files are taken from the directories given on the command line
+#[derive(Debug, Clone)]
+pub struct FileFilter {
+    include: GlobSet,
+    exclude: GlobSet,
+    /// whether there are include patterns at all, otherwise all files are included
+    has_include: bool,
+}
+
+impl FileFilter {
+    /// takes the files matching one of the `include` patterns (if any) and none of the `exclude` patterns
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        let build = |patterns: &[String]| -> Result<GlobSet> {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in patterns {
+                builder
+                    .add(Glob::new(pattern).with_context(|| format!("invalid glob {pattern:?}"))?);
+            }
+            Ok(builder.build()?)
+        };
+        Ok(FileFilter {
+            include: build(include)?,
+            exclude: build(exclude)?,
+            has_include: !include.is_empty(),
+        })
+    }
+
+    fn accepts(&self, path: &Path) -> bool {
+        (!self.has_include || self.include.is_match(path)) && !self.exclude.is_match(path)
+    }
+}
+
+/// the sources of the command line `arguments`, replacing directories by the files below them if `recursive`
+///
+/// The filter only applies to files found in directories, files given explicitly are always taken.
+pub fn expand(arguments: &[String], recursive: bool, filter: &FileFilter) -> Result<Vec<Source>> {
+    let mut sources = Vec::new();
+    for argument in arguments {
+        let source = Source::from_argument(argument);
+        match source {
+            Source::File(path) if recursive && path.is_dir() => {
+                for entry in WalkDir::new(&path).sort_by_file_name() {
+                    let entry = entry?;
+                    if entry.file_type().is_file() && filter.accepts(entry.path()) {
+                        sources.push(Source::File(entry.into_path()));
+                    }
+                }
+            }
+            source => sources.push(source),
+        }
+    }
+    Ok(sources)
+}
+
+/// how the texts are read from their sources
+#[derive(Debug, Clone, Copy)]
+pub struct InputOptions {
+    /// the number of (decoded) bytes or codepoints to skip at the beginning of each text
+    pub offset: u64,
+    /// the number of characters to keep from each (decoded) text after the offset
+    pub length: Option<u64>,
+    /// the number of characters to keep from the end of each (decoded) text, instead of offset and length
+    pub tail: Option<u64>,
+    pub encoding: InputEncoding,
+    /// splits each source into records separated by this byte
+    pub delimiter: Option<u8>,
+    pub compression: Compression,
+    /// reads each source as a tar archive whose members are the texts
+    pub archive: bool,
+    /// maps uncompressed raw files into memory instead of reading them
+    pub mmap: bool,
+    pub format: Format,
+    /// counts offset, length, and tail in UTF-8 codepoints instead of bytes
+    pub unicode: bool,
+    /// splits each (selected part of a) source into consecutive blocks of this many bytes
+    pub chunk_size: Option<u64>,
+}
+
+/// the defaults of the command line: whole raw texts of uncompressed or automatically decompressed sources
+impl Default for InputOptions {
+    fn default() -> Self {
+        InputOptions {
+            offset: 0,
+            length: None,
+            tail: None,
+            encoding: InputEncoding::Raw,
+            delimiter: None,
+            compression: Compression::Auto,
+            archive: false,
+            mmap: false,
+            format: Format::Text,
+            unicode: false,
+            chunk_size: None,
+        }
+    }
+}
+
+impl InputOptions {
+    /// calls `f` with each record of `source`, i.e., each member of an archive or each of [`InputOptions::records`]
+    pub fn for_each_record(
+        &self,
+        source: &Source,
+        mut f: impl FnMut(Record) -> Result<()>,
+    ) -> Result<()> {
+        if !self.archive {
+            return self.records(source)?.try_for_each(|record| f(record?));
+        }
+        let mut archive = tar::Archive::new(self.open(source)?.0);
+        let context = || format!("cannot read the archive {}", source.label());
+        for entry in archive.entries().with_context(context)? {
+            let entry = entry.with_context(context)?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let name = entry.path()?.display().to_string();
+            let (text, start) = self
+                .read(entry)
+                .with_context(|| format!("cannot read {}:{name}", source.label()))?;
+            let text = text.into();
+            f(Record {
+                input: source.label(),
+                name: Some(name),
+                range: self.range(&text),
+                text,
+                start,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// the records of `source`: its whole text, or its parts separated by the delimiter, which are streamed
+    ///
+    /// Offset, length, tail, and decoding apply to each record.
+    pub fn records<'a>(
+        &'a self,
+        source: &'a Source,
+    ) -> Result<Box<dyn Iterator<Item = Result<Record>> + 'a>> {
+        let sequences: Option<Box<dyn Iterator<Item = _>>> = match self.format {
+            Format::Text => None,
+            Format::Fasta => Some(Box::new(Fasta::new(self.open(source)?.0))),
+            Format::Fastq => Some(Box::new(Fastq::new(self.open(source)?.0))),
+            Format::TwoBit => Some(
+                self.two_bit(source)
+                    .with_context(|| format!("cannot read {}", source.label()))?,
+            ),
+        };
+        if let Some(records) = sequences {
+            return Ok(Box::new(records.map(move |record| {
+                let record = record.with_context(|| format!("cannot read {}", source.label()))?;
+                // the line breaks are removed, so the positions in the file are not known
+                let (text, start) = self.window(&record.sequence);
+                Ok(Record {
+                    input: source.label(),
+                    name: Some(record.id),
+                    text: text.to_vec().into(),
+                    range: None,
+                    start,
+                })
+            })));
+        }
+        if let Some(chunk_size) = self.chunk_size {
+            return self.chunks(source, chunk_size);
+        }
+        let delimiter = match self.delimiter {
+            None => {
+                let text = self.load(source);
+                return Ok(Box::new(std::iter::once(text.map(|(text, start)| {
+                    Record {
+                        input: source.label(),
+                        name: None,
+                        range: self.range(&text),
+                        text,
+                        start,
+                    }
+                }))));
+            }
+            Some(delimiter) => delimiter,
+        };
+        let records = self.open(source)?.0.split(delimiter).enumerate();
+        let mut start = 0;
+        Ok(Box::new(records.map(move |(i, record)| {
+            let mut record = record?;
+            let record_start = start;
+            start += record.len() as u64 + 1;
+            // lines may end with CRLF
+            if delimiter == b'\n' && record.last() == Some(&b'\r') {
+                record.pop();
+            }
+            let (range, _) = self.window_range(&record);
+            let range = (self.encoding == InputEncoding::Raw)
+                .then(|| record_start + range.start as u64..record_start + range.end as u64);
+            let text = decode(record, self.encoding)?;
+            let (text, start) = self.window(&text);
+            Ok(Record {
+                input: source.label(),
+                name: Some((i + 1).to_string()),
+                text: text.to_vec().into(),
+                range,
+                start,
+            })
+        })))
+    }
+
+    /// the consecutive blocks of `chunk_size` bytes of `source`, labeled with their ranges, which are streamed if possible
+    fn chunks<'a>(
+        &'a self,
+        source: &'a Source,
+        chunk_size: u64,
+    ) -> Result<Box<dyn Iterator<Item = Result<Record>> + 'a>> {
+        let streamed = self.encoding == InputEncoding::Raw && self.tail.is_none() && !self.unicode;
+        let (mut reader, mut start): (Box<dyn Read>, u64) = if streamed {
+            let mut reader = self.open(source)?.0;
+            io::copy(&mut (&mut reader).take(self.offset), &mut io::sink())?;
+            let reader = reader.take(self.length.unwrap_or(u64::MAX));
+            (Box::new(reader), self.offset)
+        } else {
+            // the windows of decoded texts are not in the source, so count in the decoded text
+            let (text, start) = self.load(source)?;
+            (Box::new(Cursor::new(text.to_vec())), start as u64)
+        };
+        Ok(Box::new(std::iter::from_fn(move || {
+            let mut chunk = Vec::new();
+            match (&mut reader).take(chunk_size).read_to_end(&mut chunk) {
+                Ok(0) => None,
+                Ok(len) => {
+                    let end = start + len as u64;
+                    let name = format!("{}..{}", start, end);
+                    start = end;
+                    Some(Ok(Record {
+                        input: source.label(),
+                        name: Some(name),
+                        text: chunk.into(),
+                        range: streamed.then(|| end - len as u64..end),
+                        start: (end - len as u64) as usize,
+                    }))
+                }
+                Err(error) => Some(Err(error).context(format!("cannot read {}", source.label()))),
+            }
+        })))
+    }
+
+    /// the sequences of the 2bit file `source`
+    fn two_bit(&self, source: &Source) -> Result<Box<dyn Iterator<Item = Result<SequenceRecord>>>> {
+        let (reader, compression) = self.open(source)?;
+        Ok(match source {
+            Source::File(path) if compression == Compression::Plain => {
+                Box::new(TwoBit::new(BufReader::new(fs::File::open(path)?))?)
+            }
+            // the sequences are located by their offsets, so other inputs are read entirely
+            _ => Box::new(TwoBit::new(Cursor::new(from_reader(reader, None)?))?),
+        })
+    }
+
+    /// opens `source`, decompressing it if necessary
+    fn open(&self, source: &Source) -> Result<(Box<dyn BufRead>, Compression)> {
+        if let Source::Concat(sources, separator) = source {
+            // each part is decompressed on its own
+            let mut reader: Box<dyn BufRead> = Box::new(io::empty());
+            for (i, part) in sources.iter().enumerate() {
+                if let Some(separator) = separator.filter(|_| i > 0) {
+                    reader = Box::new(reader.chain(Cursor::new([separator])));
+                }
+                reader = Box::new(reader.chain(self.open(part)?.0));
+            }
+            return Ok((reader, Compression::Plain));
+        }
+        let mut reader = source.open()?;
+        let extension = match source {
+            Source::File(path) => path.extension().and_then(|e| e.to_str()),
+            Source::Url(url) => Path::new(url).extension().and_then(|e| e.to_str()),
+            _ => None,
+        };
+        let compression = self.compression.detect(reader.fill_buf()?, extension);
+        Ok((compression.decoder(reader)?, compression))
+    }
+
+    /// reads and decodes the text of `source`, with the number of characters of the source before it
+    pub fn load(&self, source: &Source) -> Result<(Text, usize)> {
+        let (reader, compression) = self.open(source)?;
+        match source {
+            // only uncompressed files can be read from the middle, or mapped
+            Source::File(path)
+                if compression == Compression::Plain
+                    && self.encoding == InputEncoding::Raw
+                    && (self.mmap || !self.unicode) =>
+            {
+                if self.mmap {
+                    return self
+                        .map(path)
+                        .with_context(|| format!("cannot map {}", path.display()));
+                }
+                let read = || match self.tail {
+                    Some(tail) => {
+                        let start = fs::metadata(path)?.len().saturating_sub(tail);
+                        file_range2byte_vector(path, start, None).map(|text| (text, start))
+                    }
+                    None => file_range2byte_vector(path, self.offset, self.length)
+                        .map(|text| (text, self.offset)),
+                };
+                let (text, start) =
+                    read().with_context(|| format!("cannot read {}", path.display()))?;
+                Ok((text.into(), to_usize(start)))
+            }
+            _ => {
+                let (text, start) = self
+                    .read(reader)
+                    .with_context(|| format!("cannot read {}", source.label()))?;
+                Ok((text.into(), start))
+            }
+        }
+    }
+
+    /// the positions of `text`, the selected part of a whole source, in the (decompressed) bytes of the source, if they are known
+    fn range(&self, text: &Text) -> Option<Range<u64>> {
+        match text {
+            Text::Mapped(_, range) => Some(range.start as u64..range.end as u64),
+            // only the offset is known, the tail and codepoints depend on the whole text
+            Text::Read(text)
+                if self.encoding == InputEncoding::Raw && self.tail.is_none() && !self.unicode =>
+            {
+                Some(self.offset..self.offset + text.len() as u64)
+            }
+            Text::Read(_) => None,
+        }
+    }
+
+    /// maps the file at `path` into memory, restricted to the window, with the number of characters before it
+    fn map(&self, path: &Path) -> Result<(Text, usize)> {
+        let file = fs::File::open(path)?;
+        // SAFETY: the file is only read, and is assumed not to be modified while it is processed
+        let map = unsafe { Mmap::map(&file)? };
+        let (range, start) = self.window_range(&map);
+        Ok((Text::Mapped(map, range), start))
+    }
+
+    /// reads the decoded part of the input of `reader` selected by offset, length, and tail, with the number of characters before it
+    fn read(&self, mut reader: impl Read) -> Result<(Vec<u8>, usize)> {
+        if self.encoding == InputEncoding::Raw && self.tail.is_none() && !self.unicode {
+            io::copy(&mut (&mut reader).take(self.offset), &mut io::sink())?;
+            return Ok((from_reader(reader, self.length)?, to_usize(self.offset)));
+        }
+        // positions in the encoded text are not positions of the decoded text, so decode everything first
+        let text = decode(from_reader(reader, None)?, self.encoding)?;
+        let (window, start) = self.window(&text);
+        Ok((window.to_vec(), start))
+    }
+
+    /// the part of `text` selected by the offset and the length, or the tail, with the number of units before it
+    fn window<'a>(&self, text: &'a [u8]) -> (&'a [u8], usize) {
+        let (range, start) = self.window_range(text);
+        (&text[range], start)
+    }
+
+    /// the positions of `text` selected by the offset and the length, or the tail, with the number of units before them
+    fn window_range(&self, text: &[u8]) -> (Range<usize>, usize) {
+        if !self.unicode {
+            let range = self.unit_range(text.len());
+            return (range.clone(), range.start);
+        }
+        // the starting bytes of the codepoints, i.e., all bytes but UTF-8 continuation bytes
+        let starts = || {
+            text.iter()
+                .enumerate()
+                .filter(|(_, &byte)| byte & 0xc0 != 0x80)
+                .map(|(i, _)| i)
+        };
+        let range = self.unit_range(starts().count());
+        let position = |unit| starts().nth(unit).unwrap_or(text.len());
+        (position(range.start)..position(range.end), range.start)
+    }
+
+    /// the units, i.e., bytes or codepoints, of a text with `len` units selected by the offset and the length, or the tail
+    fn unit_range(&self, len: usize) -> Range<usize> {
+        if let Some(tail) = self.tail {
+            return len.saturating_sub(to_usize(tail))..len;
+        }
+        let start = to_usize(self.offset).min(len);
+        let end = self.length.map_or(len, |length| {
+            start.saturating_add(to_usize(length)).min(len)
+        });
+        start..end
+    }
+}
+
+/// `value`, or the largest `usize` if it does not fit
+fn to_usize(value: u64) -> usize {
+    usize::try_from(value).unwrap_or(usize::MAX)
+}
+
+/// how the input is compressed
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// detect the compression by the magic bytes at the beginning of the input
+    Auto,
+    /// the input is not compressed
+    #[clap(name = "none")]
+    Plain,
+    /// gzip, possibly with several members
+    Gzip,
+    /// Zstandard (requires the feature `zstd`)
+    Zstd,
+    /// xz (requires the feature `xz`)
+    Xz,
+    /// bzip2 (requires the feature `bzip2`)
+    Bzip2,
+}
+
+impl Compression {
+    /// magic bytes at the beginning of the compressed formats
+    const MAGIC: [(&'static [u8], Compression); 4] = [
+        (&[0x1f, 0x8b], Compression::Gzip),
+        (&[0x28, 0xb5, 0x2f, 0xfd], Compression::Zstd),
+        (&[0xfd, b'7', b'z', b'X', b'Z', 0x00], Compression::Xz),
+        (b"BZh", Compression::Bzip2),
+    ];
+
+    /// resolves [`Compression::Auto`] with the first bytes of the input, or else the file extension
+    fn detect(self, header: &[u8], extension: Option<&str>) -> Compression {
+        if self != Compression::Auto {
+            return self;
+        }
+        let by_magic = Self::MAGIC
+            .iter()
+            .find(|(magic, _)| header.starts_with(magic))
+            .map(|&(_, compression)| compression);
+        let by_extension = match extension {
+            Some("gz") => Some(Compression::Gzip),
+            Some("zst") => Some(Compression::Zstd),
+            Some("xz") => Some(Compression::Xz),
+            Some("bz2") => Some(Compression::Bzip2),
+            _ => None,
+        };
+        by_magic.or(by_extension).unwrap_or(Compression::Plain)
+    }
+
+    /// wraps `reader` in the decompressor of this format
+    fn decoder<'a>(self, reader: Box<dyn BufRead + 'a>) -> Result<Box<dyn BufRead + 'a>> {
+        Ok(match self {
+            Compression::Auto | Compression::Plain => reader,
+            Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(reader))),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Box::new(BufReader::new(zstd::Decoder::with_buffer(reader)?)),
+            #[cfg(feature = "xz")]
+            Compression::Xz => Box::new(BufReader::new(
+                xz2::bufread::XzDecoder::new_multi_decoder(reader),
+            )),
+            #[cfg(feature = "bzip2")]
+            Compression::Bzip2 => {
+                Box::new(BufReader::new(bzip2::bufread::MultiBzDecoder::new(reader)))
+            }
+            #[allow(unreachable_patterns)]
+            compression => bail!(
+                "{compression:?} decompression is not available, rebuild with the feature `{}`",
+                compression.feature()
+            ),
+        })
+    }
+
+    /// the cargo feature enabling this format
+    fn feature(self) -> &'static str {
+        match self {
+            Compression::Zstd => "zstd",
+            Compression::Xz => "xz",
+            Compression::Bzip2 => "bzip2",
+            _ => "cli",
+        }
+    }
+}
+
+/// how the texts are stored in the input
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// the input is the text
+    Text,
+    /// each sequence of a FASTA file is a text, labeled with its identifier
+    Fasta,
+    /// each read of a FASTQ file is a text, labeled with its identifier; the qualities are ignored
+    Fastq,
+    /// each sequence of a UCSC 2bit file is a text, labeled with its name
+    #[clap(name = "2bit")]
+    TwoBit,
+}
+
+/// how the input bytes are encoded
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputEncoding {
+    /// the bytes are the text
+    Raw,
+    /// the text is given as hexadecimal digits
+    Hex,
+    /// the text is given in standard base64
+    Base64,
+}
+
+/// decodes `input` according to `encoding`, ignoring ASCII whitespace in the encoded forms
+pub fn decode(input: Vec<u8>, encoding: InputEncoding) -> Result<Vec<u8>> {
+    let digits = || input.iter().copied().filter(|c| !c.is_ascii_whitespace());
+    match encoding {
+        InputEncoding::Raw => Ok(input),
+        InputEncoding::Hex => {
+            let digits: Vec<u8> = digits().collect();
+            if digits.len() % 2 == 1 {
+                bail!("odd number of hexadecimal digits");
+            }
+            let value = |c: u8| (c as char).to_digit(16);
+            digits
+                .chunks(2)
+                .map(|pair| match (value(pair[0]), value(pair[1])) {
+                    (Some(high), Some(low)) => Ok((high << 4 | low) as u8),
+                    _ => bail!(
+                        "invalid hexadecimal digits {:?}",
+                        String::from_utf8_lossy(pair)
+                    ),
+                })
+                .collect()
+        }
+        InputEncoding::Base64 => {
+            base64::decode(digits().collect::<Vec<u8>>()).context("invalid base64 input")
+        }
+    }
+}
+
+#[test]
+fn test_decode() {
+    assert_eq!(
+        decode(b"6263 63\n61".to_vec(), InputEncoding::Hex).unwrap(),
+        b"bcca"
+    );
+    assert!(decode(b"626".to_vec(), InputEncoding::Hex).is_err());
+    assert!(decode(b"+6".to_vec(), InputEncoding::Hex).is_err());
+    assert_eq!(
+        decode(b"YmNj\nYQ==".to_vec(), InputEncoding::Base64).unwrap(),
+        b"bcca"
+    );
+    assert_eq!(parse_delimiter("nul").unwrap(), 0);
+    assert_eq!(parse_delimiter("0x1e").unwrap(), 0x1e);
+    assert_eq!(parse_delimiter(",").unwrap(), b',');
+    assert!(parse_delimiter("ab").is_err());
+}
+
+#[test]
+fn test_detect_compression() {
+    let detect = |header: &[u8], extension| Compression::Auto.detect(header, extension);
+    assert_eq!(detect(&[0x1f, 0x8b, 8], None), Compression::Gzip);
+    assert_eq!(detect(&[0x28, 0xb5, 0x2f, 0xfd], None), Compression::Zstd);
+    assert_eq!(detect(b"\xfd7zXZ\x00", Some("txt")), Compression::Xz);
+    assert_eq!(detect(b"BZh9", None), Compression::Bzip2);
+    assert_eq!(detect(b"", Some("zst")), Compression::Zstd);
+    assert_eq!(detect(b"abc", None), Compression::Plain);
+    assert_eq!(Compression::Plain.detect(b"BZh9", None), Compression::Plain);
+}
+
+#[test]
+fn test_archive() {
+    let mut builder = tar::Builder::new(Vec::new());
+    for (path, text) in [("a.txt", &b"bccadbaccbcd"[..]), ("d/b.txt", b"aaaba")] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(text.len() as u64);
+        header.set_cksum();
+        builder.append_data(&mut header, path, text).unwrap();
+    }
+    let path = std::env::temp_dir().join(format!("lls-archive-{}.tar", std::process::id()));
+    fs::write(&path, builder.into_inner().unwrap()).unwrap();
+    let options = InputOptions {
+        offset: 1,
+        archive: true,
+        ..Default::default()
+    };
+    let mut records = Vec::new();
+    let source = Source::File(path.clone());
+    options
+        .for_each_record(&source, |record| {
+            records.push(record);
+            Ok(())
+        })
+        .unwrap();
+    let label = source.label();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].label(), format!("{label}:a.txt"));
+    assert_eq!(&records[0].text[..], b"ccadbaccbcd");
+    assert_eq!(records[1].label(), format!("{label}:d/b.txt"));
+    assert_eq!(&records[1].text[..], b"aaba");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_mmap() {
+    let path = std::env::temp_dir().join(format!("lls-mmap-{}.txt", std::process::id()));
+    fs::write(&path, b"bccadbaccbcd").unwrap();
+    let mut options = InputOptions {
+        offset: 3,
+        length: Some(4),
+        mmap: true,
+        ..Default::default()
+    };
+    let source = Source::File(path.clone());
+    let (text, start) = options.load(&source).unwrap();
+    assert!(matches!(text, Text::Mapped(..)));
+    assert_eq!((&text[..], start), (&b"adba"[..], 3));
+    options.tail = Some(3);
+    let (text, start) = options.load(&source).unwrap();
+    assert_eq!((&text[..], start), (&b"bcd"[..], 9));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_unicode_window() {
+    let mut options = InputOptions {
+        offset: 1,
+        length: Some(2),
+        unicode: true,
+        ..Default::default()
+    };
+    let text = "äöüaäö".as_bytes();
+    assert_eq!(options.window(text), ("öü".as_bytes(), 1));
+    options.tail = Some(4);
+    assert_eq!(options.window(text), ("üaäö".as_bytes(), 2));
+    options.unicode = false;
+    assert_eq!(options.window(text), ("äö".as_bytes(), 7));
+}
+
+#[test]
+fn test_from_argument() {
+    assert!(matches!(Source::from_argument("-"), Source::Stdin));
+    assert!(matches!(
+        Source::from_argument("https://example.com/a.fa"),
+        Source::Url(_)
+    ));
+    assert!(matches!(Source::from_argument("http.txt"), Source::File(_)));
+}
+
+#[test]
+fn test_chunks() {
+    let options = InputOptions {
+        offset: 2,
+        length: Some(9),
+        chunk_size: Some(4),
+        ..Default::default()
+    };
+    let source = Source::Inline("bccadbaccbcd".to_owned());
+    let chunks: Vec<(String, Vec<u8>)> = options
+        .records(&source)
+        .unwrap()
+        .map(|record| record.map(|record| (record.label(), record.text.to_vec())))
+        .collect::<Result<_>>()
+        .unwrap();
+    let expected = [
+        ("text:2..6", &b"cadb"[..]),
+        ("text:6..10", b"accb"),
+        ("text:10..11", b"c"),
+    ];
+    assert_eq!(chunks.len(), expected.len());
+    for ((label, text), (expected_label, expected_text)) in chunks.iter().zip(expected) {
+        assert_eq!(
+            (label.as_str(), text.as_slice()),
+            (expected_label, expected_text)
+        );
+    }
+}
+
+#[test]
+fn test_concat() {
+    let options = InputOptions::default();
+    let parts = || {
+        vec![
+            Source::Inline("bcca".to_owned()),
+            Source::Inline("dbac".to_owned()),
+        ]
+    };
+    let (text, _) = options.load(&Source::Concat(parts(), None)).unwrap();
+    assert_eq!(&text[..], b"bccadbac");
+    let source = Source::Concat(parts(), Some(b'\n'));
+    assert_eq!(&options.load(&source).unwrap().0[..], b"bcca\ndbac");
+    assert_eq!(source.label(), "text+text");
+}
+
+#[test]
+fn test_record_ranges() {
+    let mut options = InputOptions {
+        offset: 1,
+        delimiter: Some(b'\n'),
+        ..Default::default()
+    };
+    let source = Source::Inline("ab\r\ncde\nf".to_owned());
+    let ranges = |options: &InputOptions| -> Vec<Option<Range<u64>>> {
+        options
+            .records(&source)
+            .unwrap()
+            .map(|record| record.unwrap().range)
+            .collect()
+    };
+    assert_eq!(ranges(&options), [Some(1..2), Some(5..7), Some(9..9)]);
+    options.delimiter = None;
+    assert_eq!(ranges(&options), [Some(1..9)]);
+    options.tail = Some(3);
+    assert_eq!(ranges(&options), [None]);
+    options.tail = None;
+    options.chunk_size = Some(5);
+    assert_eq!(ranges(&options), [Some(1..6), Some(6..9)]);
+    options.chunk_size = None;
+    options.delimiter = None;
+    options.format = Format::Fasta;
+    options.offset = 4;
+    let source = Source::Inline(">chr1\nTTTT\nacab\n".to_owned());
+    let record = options.records(&source).unwrap().next().unwrap().unwrap();
+    assert_eq!((&record.text[..], record.start), (&b"acab"[..], 4));
+}
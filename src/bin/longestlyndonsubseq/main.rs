@@ -0,0 +1,1309 @@
+use std::borrow::Cow;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+
+use anyhow::{bail, Context, Result};
+use indicatif::{ProgressBar, ProgressStyle};
+
+use longestlyndonsubseq::{
+    is_lyndon, iter_lyndon_subsequences, longest_lyndon_subsequence,
+    longest_lyndon_subsequence_by_key, longest_lyndon_subsequence_chars,
+    longest_lyndon_subsequence_len, longest_lyndon_subsequence_with_progress, lyndon_factorization,
+    top_k_longest, top_k_longest_by_key, BatchSolver, LyndonResult, LyndonStructure, Solver,
+};
+
+mod cache;
+mod config;
+mod generate;
+mod input;
+mod output;
+mod repl;
+mod sequence;
+#[cfg(feature = "serve")]
+mod serve;
+mod stats;
+mod symbols;
+mod trace;
+mod tree;
+
+use cache::Cache;
+use input::{Compression, FileFilter, Format, InputEncoding, InputOptions, Record, Source};
+use output::{Escape, Output, OutputFormat, PositionEncoding};
+use stats::{SearchDetail, Stats, Summary};
+use symbols::{Alphabet, CharFilter, CharSet, Collation, Order, Unlisted};
+
+/// Computes the longest Lyndon subsequence
+#[derive(Parser, Debug)]
+#[clap(args_override_self = true, args_conflicts_with_subcommands = true)]
+struct Cli {
+    /// the options of `subsequence`, which is the default command
+    #[clap(flatten)]
+    args: Args,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+/// the subcommands given the options of the configuration file, see [`config::with_config`]
+const CONFIGURED_COMMANDS: &[&str] = &[
+    "subsequence",
+    "factorize",
+    "check",
+    "enumerate",
+    "repl",
+    "serve",
+];
+
+/// the length from which texts get a progress bar on a terminal
+const PROGRESS_MIN_LEN: usize = 1 << 12;
+
+/// the options for processing texts
+#[derive(clap::Args, Debug)]
+#[clap(args_override_self = true)]
+struct Args {
+    /// read default options from this TOML file instead of `lls.toml` in the current directory or in `~/.config/lls`
+    #[clap(long)]
+    config: Option<PathBuf>,
+
+    /// input filenames, `-` or omitted for the standard input
+    #[clap(short, long, multiple_values = true)]
+    filename: Vec<String>,
+
+    /// process the files in the given directories and their subdirectories
+    #[clap(short, long)]
+    recursive: bool,
+
+    /// only process the files in directories matching one of these glob patterns, e.g., `*.txt`
+    #[clap(long, multiple_values = true, requires = "recursive")]
+    include: Vec<String>,
+
+    /// skip the files in directories matching one of these glob patterns
+    #[clap(long, multiple_values = true, requires = "recursive")]
+    exclude: Vec<String>,
+
+    /// the input text itself, instead of a file
+    #[clap(short, long, conflicts_with = "filename")]
+    text: Option<String>,
+
+    /// the number of characters to read from the input file (after decoding)
+    #[clap(short, long, visible_alias = "length")]
+    prefix: Option<u64>,
+
+    /// the number of characters to skip at the beginning of the input (after decoding)
+    #[clap(short, long, default_value = "0")]
+    offset: u64,
+
+    /// the characters to read as a half-open range like `1000..5000`
+    #[clap(long, conflicts_with_all = &["prefix", "offset"], parse(try_from_str = parse_range))]
+    range: Option<Range<u64>>,
+
+    /// the number of characters to read from the end of the input (after decoding)
+    #[clap(long, conflicts_with_all = &["prefix", "offset", "range"])]
+    tail: Option<u64>,
+
+    /// how the input is compressed; the other options apply to the decompressed input
+    #[clap(long, arg_enum, default_value = "auto")]
+    decompress: Compression,
+
+    /// how the input is encoded
+    #[clap(long, arg_enum, default_value = "raw")]
+    input_encoding: InputEncoding,
+
+    /// process the records separated by this byte (`nul`, `newline`, `tab`, `0x1e`, or a character) individually
+    #[clap(short, long, parse(try_from_str = input::parse_delimiter))]
+    delimiter: Option<u8>,
+
+    /// process each line individually, same as `--delimiter newline`
+    #[clap(short, long, conflicts_with = "delimiter")]
+    lines: bool,
+
+    /// read the inputs as (possibly compressed) tar archives and process each member individually
+    #[clap(short, long, conflicts_with_all = &["delimiter", "lines", "text"])]
+    archive: bool,
+
+    /// map uncompressed files into memory instead of copying them, which saves memory on large inputs
+    #[clap(long, conflicts_with_all = &["delimiter", "lines", "archive"])]
+    mmap: bool,
+
+    /// how the texts are stored in the input; offset, length, and tail apply to each sequence
+    #[clap(long, arg_enum, default_value = "text", env = "LLS_FORMAT", conflicts_with_all = &["delimiter", "lines", "archive", "mmap", "input-encoding"])]
+    format: Format,
+
+    /// only output the record with the longest result among all inputs, e.g., the best read of a FASTQ run
+    #[clap(long)]
+    best: bool,
+
+    /// decode the input as UTF-8 and compare codepoints instead of bytes; offset, length, and tail count codepoints
+    #[clap(short, long)]
+    unicode: bool,
+
+    /// compare the extended grapheme clusters of the UTF-8 input instead of bytes; offset, length, and tail count codepoints
+    #[clap(short, long, conflicts_with = "unicode")]
+    graphemes: bool,
+
+    /// how grapheme clusters are compared
+    #[clap(long, arg_enum, default_value = "codepoint", requires = "graphemes")]
+    collation: Collation,
+
+    /// compare the words separated by whitespace instead of bytes, and output the words of the result
+    #[clap(long, conflicts_with_all = &["unicode", "graphemes"])]
+    tokens: bool,
+
+    /// compare letters regardless of their case, i.e., ASCII letters, or all letters by simple case folding with `--unicode`; the output keeps the original case
+    #[clap(short, long)]
+    ignore_case: bool,
+
+    /// only keep these characters of the input, given like `a-z0-9` with the escapes `\n`, `\r`, `\t`, `\0`, `\\`, `\-`, and `\xNN`
+    #[clap(long, parse(try_from_str = symbols::parse_char_set))]
+    include_chars: Option<CharSet>,
+
+    /// drop these characters of the input, e.g., `\n\r` for the line breaks
+    #[clap(long, parse(try_from_str = symbols::parse_char_set))]
+    exclude_chars: Option<CharSet>,
+
+    /// drop all ASCII whitespace of the input, such as line breaks
+    #[clap(short = 'w', long)]
+    strip_whitespace: bool,
+
+    /// compare the characters in this order, e.g., `TGCA`, given like `--include-chars`
+    #[clap(long, env = "LLS_ALPHABET", parse(try_from_str = symbols::parse_alphabet), conflicts_with_all = &["graphemes", "tokens"])]
+    alphabet: Option<Alphabet>,
+
+    /// whether characters not in the alphabet are an error or larger than all characters of the alphabet
+    #[clap(
+        long,
+        arg_enum,
+        default_value = "error",
+        env = "LLS_UNLISTED",
+        requires = "alphabet"
+    )]
+    unlisted: Unlisted,
+
+    /// invert the order of the characters, which computes a longest anti-Lyndon subsequence
+    #[clap(long, conflicts_with_all = &["graphemes", "tokens"])]
+    reverse_alphabet: bool,
+
+    /// the number of threads searching a text (requires the feature `rayon`), 0 for one per core; only the searches for a longest subsequence of bytes or codepoints in their natural order use several
+    #[clap(long, default_value = "1", env = "LLS_THREADS")]
+    threads: usize,
+
+    /// process consecutive blocks of this many bytes of each input individually
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..), conflicts_with_all = &["delimiter", "lines", "archive", "mmap", "format", "unicode", "graphemes", "tokens"])]
+    chunk_size: Option<u64>,
+
+    /// process all inputs as one text
+    #[clap(long, conflicts_with_all = &["archive", "mmap"])]
+    concat: bool,
+
+    /// byte put between two inputs with `--concat`, given like `--delimiter`
+    #[clap(long, requires = "concat", parse(try_from_str = input::parse_delimiter))]
+    separator: Option<u8>,
+
+    /// keep running and process the input files again whenever one of them changes
+    #[clap(long, conflicts_with = "text")]
+    watch: bool,
+
+    /// how the results are written
+    #[clap(long, arg_enum, default_value_t = OutputFormat::Text, env = "LLS_OUTPUT_FORMAT")]
+    output_format: OutputFormat,
+
+    /// also write the positions of the subsequence in the text, after a tab; they count characters with `--unicode`, from the beginning of the input or sequence also with `--offset`, `--range` or `--tail`, and include those dropped by filters
+    #[clap(long, conflicts_with_all = &["graphemes", "tokens"])]
+    positions: bool,
+
+    /// write the trie of subsequences explored by the search in each text to FILE as Graphviz graphs, with the Lyndon subsequences doubly framed, pruned branches dashed, and the result in red, e.g., for teaching; the positions count the characters kept by the filters
+    #[clap(long, value_name = "FILE", conflicts_with_all = &["graphemes", "tokens"])]
+    dump_tree: Option<PathBuf>,
+
+    /// keep the results of each text in DIR, keyed by the hash of the text and of the options that affect them, and take them from there when they are computed again
+    #[clap(long, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// write every step of the search in each text to the standard error as a JSON object per line, i.e., each push and pop of a character, successor query, update of the lengths that prune the search, and pruned branch, followed by the result; the positions count the characters kept by the filters
+    #[clap(long, conflicts_with_all = &["graphemes", "tokens"])]
+    trace: bool,
+
+    /// also write how the subsequence is composed: its period, and the decomposition of it without its last character into a power of a Lyndon root and an immature prefix of the root
+    #[clap(long, conflicts_with_all = &["graphemes", "tokens", "length-only"])]
+    structure: bool,
+
+    /// how the positions are written, e.g., as runs of adjacent positions for long subsequences
+    #[clap(long, arg_enum, value_name = "ENCODING", default_value_t = PositionEncoding::List)]
+    position_encoding: PositionEncoding,
+
+    /// write each text with the characters of its subsequence highlighted in color instead of the subsequence, for texts of at most 64 KiB
+    #[clap(long, conflicts_with_all = &["graphemes", "tokens", "length-only", "hex"])]
+    highlight: bool,
+
+    /// write each text with `^` under the characters of its subsequence instead of the subsequence, in rows of WIDTH characters (80 if not given, 0 for a single row), for texts of at most 64 KiB
+    #[clap(
+        long,
+        value_name = "WIDTH",
+        min_values = 0,
+        max_values = 1,
+        require_equals = true,
+        default_missing_value = "80",
+        conflicts_with_all = &["graphemes", "tokens", "length-only", "hex", "highlight", "positions"]
+    )]
+    align: Option<usize>,
+
+    /// write a summary of the run to the standard error: the wall-clock time, peak memory, input size, number of distinct symbols and longest result length, as JSON with the output formats `json` and `ndjson`
+    #[clap(long)]
+    stats: bool,
+
+    /// also search each text again, counting its steps, and write histograms of the stack depth, of the number of visited extensions of each subsequence, and of the number of positions that each successor query would scan to the standard error, as JSON with the output formats `json` and `ndjson`
+    #[clap(long, conflicts_with_all = &["graphemes", "tokens"])]
+    stats_detail: bool,
+
+    /// write an aggregate of the results to the standard error: the number of texts, the input size, the mean and median result length, the text with the longest result, and a histogram of the result lengths, as JSON with the output formats `json` and `ndjson`
+    #[clap(long)]
+    summary: bool,
+
+    /// check each result independently after computing it, i.e., that its positions increase, that the characters at them are the subsequence, and that it is a Lyndon word, and fail otherwise
+    #[clap(long, conflicts_with_all = &["graphemes", "tokens", "length-only"])]
+    verify: bool,
+
+    /// write nothing but the results and errors, i.e., no messages and no `--stats` or `--summary`, e.g., for command substitutions in scripts
+    #[clap(short, long)]
+    quiet: bool,
+
+    /// write what the tool does to the standard error with timings, -v for each text, -vv also for reading and filtering it, -vvv also for the search of each starting character
+    #[clap(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// exit with the status 1 unless the longest result has at least this length (errors exit with 2), e.g., for shell conditionals
+    #[clap(long, value_name = "LENGTH")]
+    min_length: Option<usize>,
+
+    /// only write the results with at least this length, e.g., the interesting records of a large FASTA file; the results of records separated by a delimiter are then labeled
+    #[clap(long, value_name = "LENGTH")]
+    filter_min_length: Option<usize>,
+
+    /// write the K longest distinct Lyndon subsequences of each text instead of one longest, longest first
+    #[clap(long, value_name = "K", conflicts_with_all = &["graphemes", "tokens", "length-only", "best"])]
+    top_k: Option<usize>,
+
+    /// write the length of the longest Lyndon subsequence of every STRIDE-th prefix of each text and of the whole text instead, as lines with the prefix length and a tab; each prefix is searched on its own, so STRIDE defaults to a hundredth of the text, which costs about 50 searches of the whole text, and `--per-prefix=1` costs as many searches as the text has characters
+    #[clap(
+        long,
+        value_name = "STRIDE",
+        require_equals = true,
+        conflicts_with_all = &["graphemes", "tokens", "best", "top-k", "positions"]
+    )]
+    per_prefix: Option<Option<usize>>,
+
+    /// also write the size of each input, the positions of each text in it (if known), and the SHA-256 hash of the text, in the output formats but `text`, `bed` and `gff`
+    #[clap(long)]
+    provenance: bool,
+
+    /// only write the length of the subsequence, which is faster to compute for bytes in their natural order
+    #[clap(long, conflicts_with = "positions")]
+    length_only: bool,
+
+    /// write the results to this file instead of the standard output, with the subsequences as raw bytes, and without a line break if there is a single text
+    #[clap(long)]
+    output: Option<PathBuf>,
+
+    /// write the bytes of the subsequences as hexadecimal digits, e.g., for binary inputs
+    #[clap(long)]
+    hex: bool,
+
+    /// when to write the bytes of text results that are not printable UTF-8 as `\xNN`
+    #[clap(long, arg_enum, default_value_t = Escape::Auto)]
+    escape: Escape,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// compute the longest Lyndon subsequence of each text, which is the default
+    Subsequence(Args),
+    /// write the Lyndon factors of each text, one per line
+    Factorize(Args),
+    /// write whether each text is a Lyndon word, and fail if one is not
+    Check(Args),
+    /// write every distinct Lyndon subsequence of each text up to a length, one per line in lexicographic order
+    Enumerate {
+        #[clap(flatten)]
+        args: Args,
+
+        /// the length of the longest written subsequences
+        #[clap(long, value_name = "LENGTH")]
+        max_len: usize,
+
+        /// only write the number of the subsequences
+        #[clap(long)]
+        count: bool,
+    },
+    Generate(generate::GenerateArgs),
+    /// type texts to see their longest Lyndon subsequences, starting with the order given by the options
+    Repl(Args),
+    /// answer `POST /lls` requests with the longest Lyndon subsequence of their body as JSON, computed like given by the options
+    Serve {
+        #[clap(flatten)]
+        args: Args,
+
+        #[clap(long, default_value_t = 8080)]
+        port: u16,
+
+        /// the address to listen on, e.g., `0.0.0.0` for all interfaces
+        #[clap(long, default_value = "127.0.0.1")]
+        address: String,
+
+        /// the longest request body in bytes, longer ones are answered with 413 Payload Too Large
+        #[clap(long, default_value_t = 1 << 24)]
+        max_body: u64,
+    },
+    /// write the completion script for a shell to the standard output
+    Completions {
+        #[clap(arg_enum)]
+        shell: Shell,
+    },
+}
+
+/// parses a range `start..end`
+fn parse_range(range: &str) -> Result<Range<u64>> {
+    let (start, end) = range
+        .split_once("..")
+        .with_context(|| format!("expected a range like 1000..5000, got {range:?}"))?;
+    let (start, end): (u64, u64) = (start.parse()?, end.parse()?);
+    if start > end {
+        bail!("the range {range:?} ends before it starts");
+    }
+    Ok(start..end)
+}
+
+/// what is computed for each text
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Task {
+    Subsequence,
+    Factorize,
+    Check,
+    Enumerate { max_len: usize, count: bool },
+}
+
+fn main() {
+    if let Err(error) = try_main() {
+        eprintln!("Error: {error:?}");
+        // like for grep, the exit status 1 is a negative answer, see `check` and `--min-length`
+        std::process::exit(2);
+    }
+}
+
+fn try_main() -> Result<()> {
+    let args = config::with_config(std::env::args_os().collect(), CONFIGURED_COMMANDS)?;
+    let cli = Cli::parse_from(args);
+    match cli.command {
+        None => process(cli.args, Task::Subsequence, None),
+        Some(Command::Subsequence(args)) => process(args, Task::Subsequence, None),
+        Some(Command::Factorize(args)) => process(args, Task::Factorize, None),
+        Some(Command::Check(args)) => process(args, Task::Check, None),
+        Some(Command::Enumerate {
+            args,
+            max_len,
+            count,
+        }) => process(args, Task::Enumerate { max_len, count }, None),
+        Some(Command::Generate(generate)) => generate::run(&generate),
+        Some(Command::Repl(args)) => repl::run(repl::Session {
+            ignore_case: args.ignore_case,
+            alphabet: args.alphabet,
+            unlisted: args.unlisted,
+            reverse: args.reverse_alphabet,
+        }),
+        Some(Command::Serve {
+            args,
+            port,
+            address,
+            max_body,
+        }) => process(args, Task::Subsequence, Some((address, port, max_body))),
+        Some(Command::Completions { shell }) => {
+            let mut command = Cli::command();
+            let name = command.get_name().to_owned();
+            clap_complete::generate(shell, &mut command, name, &mut io::stdout());
+            Ok(())
+        }
+    }
+}
+
+/// runs the `task` on the inputs given by `args`, or answers requests on the address and port of `serve`, with bodies of at most the given size, instead
+fn process(args: Args, task: Task, serve: Option<(String, u16, u64)>) -> Result<()> {
+    init_logging(args.verbose, args.quiet);
+    if args.best && task != Task::Subsequence {
+        bail!("--best only applies to computing subsequences");
+    }
+    if args.min_length.is_some() && task != Task::Subsequence {
+        bail!("--min-length only applies to computing subsequences");
+    }
+    let described = !matches!(
+        args.output_format,
+        OutputFormat::Text | OutputFormat::Bed | OutputFormat::Gff
+    );
+    if args.provenance && (!described || task != Task::Subsequence) {
+        bail!("--provenance only applies to computing subsequences in the output formats json, ndjson, msgpack, cbor, csv and tsv");
+    }
+    if args.dump_tree.is_some() && task != Task::Subsequence {
+        bail!("--dump-tree only applies to computing subsequences");
+    }
+    if args.cache_dir.is_some() && (task != Task::Subsequence || args.per_prefix.is_some()) {
+        bail!("--cache-dir only applies to computing subsequences");
+    }
+    if args.stats_detail && task != Task::Subsequence {
+        bail!("--stats-detail only applies to computing subsequences");
+    }
+    if args.trace && task != Task::Subsequence {
+        bail!("--trace only applies to computing subsequences");
+    }
+    if args.summary && (task != Task::Subsequence || args.per_prefix.is_some()) {
+        bail!("--summary only applies to computing subsequences");
+    }
+    if args.filter_min_length.is_some() && task != Task::Subsequence {
+        bail!("--filter-min-length only applies to computing subsequences");
+    }
+    if args.top_k.is_some() && task != Task::Subsequence {
+        bail!("--top-k only applies to computing subsequences");
+    }
+    if args.output_format != OutputFormat::Text && task != Task::Subsequence {
+        bail!("--output-format only applies to computing subsequences");
+    }
+    if args.highlight && (args.output_format != OutputFormat::Text || task != Task::Subsequence) {
+        bail!("--highlight only applies to computing subsequences in the output format text");
+    }
+    if args.align.is_some()
+        && (args.output_format != OutputFormat::Text || task != Task::Subsequence)
+    {
+        bail!("--align only applies to computing subsequences in the output format text");
+    }
+    if args.per_prefix.is_some()
+        && (args.output_format != OutputFormat::Text || task != Task::Subsequence)
+    {
+        bail!("--per-prefix only applies to computing subsequences in the output format text");
+    }
+    if args.per_prefix == Some(Some(0)) {
+        bail!("the stride of --per-prefix must be positive");
+    }
+    #[cfg(feature = "rayon")]
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(args.threads)
+        .build_global()?;
+    #[cfg(not(feature = "rayon"))]
+    if args.threads != 1 {
+        bail!("searching with several threads requires the feature `rayon`");
+    }
+    let sources: Vec<Source> = match &args.text {
+        Some(text) => vec![Source::Inline(text.clone())],
+        None if args.filename.is_empty() => vec![Source::Stdin],
+        None => input::expand(
+            &args.filename,
+            args.recursive,
+            &FileFilter::new(&args.include, &args.exclude)?,
+        )?,
+    };
+    let sources = match sources.len() {
+        2.. if args.concat => vec![Source::Concat(sources, args.separator)],
+        _ => sources,
+    };
+    let (offset, length) = match &args.range {
+        Some(range) => (range.start, Some(range.end - range.start)),
+        None => (args.offset, args.prefix),
+    };
+    let options = InputOptions {
+        offset,
+        length,
+        tail: args.tail,
+        encoding: args.input_encoding,
+        delimiter: if args.lines {
+            Some(b'\n')
+        } else {
+            args.delimiter
+        },
+        compression: args.decompress,
+        archive: args.archive,
+        mmap: args.mmap,
+        format: args.format,
+        unicode: args.unicode || args.graphemes,
+        chunk_size: args.chunk_size,
+    };
+
+    let labeled = labeled(&args, &options, sources.len());
+    let filter = CharFilter {
+        include: args.include_chars.clone(),
+        exclude: args.exclude_chars.clone(),
+        strip_whitespace: args.strip_whitespace,
+    };
+    let order = Order::new(
+        args.ignore_case,
+        args.unicode,
+        args.alphabet.clone(),
+        args.unlisted,
+        args.reverse_alphabet,
+    );
+    // the other searches run on one thread
+    let parallel = task == Task::Subsequence
+        && order.is_natural()
+        && !args.graphemes
+        && !args.tokens
+        && args.top_k.is_none()
+        && args.per_prefix.is_none();
+    if args.threads != 1 && !parallel {
+        log::warn!("ignoring --threads, since only longest subsequences of bytes or codepoints in their natural order are searched with several threads");
+    }
+    let mut solver = BatchSolver::new();
+    if let Some((address, port, max_body)) = serve {
+        #[cfg(feature = "serve")]
+        return serve::run(&address, port, max_body, args.quiet, |text| {
+            let record = Record {
+                input: "request".to_owned(),
+                name: None,
+                text: text.into(),
+                range: None,
+                start: 0,
+            };
+            solve(&args, &filter, &order, &mut solver, &record)
+        });
+        #[cfg(not(feature = "serve"))]
+        {
+            let _ = max_body;
+            bail!("cannot listen on {address}:{port}, rebuild with the feature `serve`");
+        }
+    }
+    let mut run = || -> Result<bool> {
+        let several = !args.best
+            && (labeled || options.delimiter.is_some() || args.top_k.is_some_and(|k| k > 1));
+        let out: Box<dyn Write> = match &args.output {
+            Some(path) => Box::new(BufWriter::new(
+                File::create(path).with_context(|| format!("cannot create {}", path.display()))?,
+            )),
+            None => Box::new(io::stdout().lock()),
+        };
+        let mut output = Output::new(out, args.output_format, labeled, several)
+            .with_positions(args.positions)
+            .with_position_encoding(args.position_encoding)
+            .with_raw(args.output.is_some())
+            .with_escape(match args.escape {
+                Escape::Auto => args.output.is_none() && io::stdout().is_terminal(),
+                Escape::Always => true,
+                Escape::Never => false,
+            })
+            .with_hex(args.hex)
+            .with_highlight(args.highlight)
+            .with_alignment(args.align)
+            .with_provenance(args.provenance)
+            .with_structure(args.structure);
+        let mut tree = match &args.dump_tree {
+            Some(path) => {
+                Some(BufWriter::new(File::create(path).with_context(|| {
+                    format!("cannot create {}", path.display())
+                })?))
+            }
+            None => None,
+        };
+        let mut stats = Stats::new();
+        let mut summary = Summary::new();
+        let mut detail = SearchDetail::new();
+        let cache = match &args.cache_dir {
+            Some(dir) => Some(Cache::new(dir, &args)?),
+            None => None,
+        };
+        let mut best: Option<(Record, Solution, Duration, Option<u64>)> = None;
+        let mut all_lyndon = true;
+        let mut longest = 0;
+        for source in &sources {
+            let size = source.size().filter(|_| args.provenance);
+            output.set_source_size(size);
+            let mut read = Instant::now();
+            options.for_each_record(source, |record| {
+                log::debug!(
+                    "read {} ({} bytes) in {:?}",
+                    record.label(),
+                    record.text.len(),
+                    read.elapsed()
+                );
+                let result = (|| {
+                    match task {
+                        Task::Subsequence if args.per_prefix.is_some() => {
+                            let symbols = Symbols::new(&args, &filter, &order, &record)?;
+                            stats.add_text(record.text.len(), Some(&symbols.keys));
+                            let stride = args.per_prefix.flatten();
+                            for (end, length) in per_prefix(&symbols.keys, stride) {
+                                longest = longest.max(length);
+                                stats.add_result(length);
+                                let line = format!("{end}\t{length}");
+                                output.write_line(&record.label(), line.as_bytes())?;
+                            }
+                            return Ok(());
+                        }
+                        Task::Subsequence => {}
+                        Task::Factorize => {
+                            let symbols = Symbols::new(&args, &filter, &order, &record)?;
+                            stats.add_text(record.text.len(), Some(&symbols.keys));
+                            for range in lyndon_factorization(&symbols.keys) {
+                                output.write_line(&record.label(), symbols.get(range))?;
+                            }
+                            return Ok(());
+                        }
+                        Task::Check => {
+                            let symbols = Symbols::new(&args, &filter, &order, &record)?;
+                            stats.add_text(record.text.len(), Some(&symbols.keys));
+                            let lyndon = is_lyndon(&symbols.keys);
+                            all_lyndon &= lyndon;
+                            return output
+                                .write_line(&record.label(), lyndon.to_string().as_bytes());
+                        }
+                        Task::Enumerate { max_len, count } => {
+                            let symbols = Symbols::new(&args, &filter, &order, &record)?;
+                            stats.add_text(record.text.len(), Some(&symbols.keys));
+                            let words = iter_lyndon_subsequences(&symbols.keys, max_len);
+                            if count {
+                                let count = words.count().to_string();
+                                return output.write_line(&record.label(), count.as_bytes());
+                            }
+                            for positions in words {
+                                let word: Vec<u8> = positions
+                                    .iter()
+                                    .flat_map(|&i| symbols.get(i..i + 1))
+                                    .copied()
+                                    .collect();
+                                output.write_line(&record.label(), &word)?;
+                            }
+                            return Ok(());
+                        }
+                    }
+                    // the keys for the instrumented searches and the statistics, grapheme clusters and words have none
+                    let keyed = tree.is_some() || args.stats_detail || args.trace || args.stats;
+                    let symbols = match keyed && !args.graphemes && !args.tokens {
+                        true => Some(Symbols::new(&args, &filter, &order, &record)?),
+                        false => None,
+                    };
+                    if let (Some(tree), Some(symbols)) = (&mut tree, &symbols) {
+                        tree::write_tree(tree, &record.label(), symbols)?;
+                    }
+                    if let Some(symbols) = symbols.as_ref().filter(|_| args.stats_detail) {
+                        detail.add(&symbols.keys);
+                    }
+                    if let Some(symbols) = symbols.as_ref().filter(|_| args.trace) {
+                        let mut err = BufWriter::new(io::stderr().lock());
+                        trace::write_trace(&mut err, &record.label(), symbols)?;
+                        err.flush()?;
+                    }
+                    let started = Instant::now();
+                    let cached = cache.as_ref().and_then(|cache| cache.get(&record.text));
+                    let results = match cached {
+                        Some(results) => {
+                            log::debug!("found {} in the cache", record.label());
+                            results
+                        }
+                        None => {
+                            let results = match args.top_k {
+                                Some(k) => solve_top_k(&args, &filter, &order, &record, k)?,
+                                None => vec![solve(&args, &filter, &order, &mut solver, &record)?],
+                            };
+                            if let Some(cache) = &cache {
+                                cache.put(&record.text, &results)?;
+                            }
+                            results
+                        }
+                    };
+                    let elapsed = started.elapsed();
+                    // the first result is a longest one, if any
+                    let length = results.first().map_or(0, Solution::len);
+                    log::info!("{}: length {length} in {elapsed:?}", record.label());
+                    longest = longest.max(length);
+                    if args.verify {
+                        for result in &results {
+                            verify(&order, &record, result)
+                                .with_context(|| format!("wrong result for {}", record.label()))?;
+                        }
+                    }
+                    if args.stats {
+                        let keys = symbols.as_ref().map(|symbols| &symbols.keys[..]);
+                        stats.add_text(record.text.len(), keys);
+                        stats.add_result(length);
+                    }
+                    summary.add(&record.label(), record.text.len(), length);
+                    let mut results = results.into_iter().filter(|result| {
+                        args.filter_min_length.is_none_or(|min| result.len() >= min)
+                    });
+                    if !args.best {
+                        return results
+                            .try_for_each(|result| output.write_result(&record, &result, elapsed));
+                    }
+                    let Some(result) = results.next() else {
+                        return Ok(());
+                    };
+                    if best
+                        .as_ref()
+                        .is_none_or(|(_, longest, ..)| longest.len() < result.len())
+                    {
+                        // the text is not needed anymore, unless for its hash
+                        let record = match args.provenance {
+                            true => record,
+                            false => Record {
+                                text: Vec::new().into(),
+                                ..record
+                            },
+                        };
+                        best = Some((record, result, elapsed, size));
+                    }
+                    Ok(())
+                })();
+                read = Instant::now();
+                result
+            })?;
+        }
+        if let Some((record, result, elapsed, size)) = &best {
+            output.set_source_size(*size);
+            output.write_result(record, result, *elapsed)?;
+        }
+        output.finish()?;
+        if let Some(tree) = &mut tree {
+            tree.flush()?;
+        }
+        let json = matches!(
+            args.output_format,
+            OutputFormat::Json | OutputFormat::Ndjson
+        );
+        if args.stats && !args.quiet {
+            stats.write(json)?;
+        }
+        if args.stats_detail && !args.quiet {
+            detail.write(json)?;
+        }
+        if args.summary && !args.quiet {
+            summary.write(json)?;
+        }
+        Ok(all_lyndon && args.min_length.is_none_or(|min| longest >= min))
+    };
+    if !args.watch {
+        if !run()? {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    let paths: Vec<&Path> = sources.iter().flat_map(Source::paths).collect();
+    if paths.is_empty() {
+        bail!("--watch requires input files");
+    }
+    let modified = || -> Vec<_> {
+        paths
+            .iter()
+            .map(|path| {
+                fs::metadata(path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+            })
+            .collect()
+    };
+    let mut last = modified();
+    loop {
+        // a file may be invalid while it is being edited, so errors do not end watching
+        if let Err(error) = run() {
+            eprintln!("Error: {error:#}");
+        }
+        while modified() == last {
+            thread::sleep(Duration::from_millis(500));
+        }
+        last = modified();
+    }
+}
+
+/// whether the results of the `sources` read with `options` need the labels of their texts, i.e., whether there may be several texts, or fewer results than records such that their order does not tell them apart
+fn labeled(args: &Args, options: &InputOptions, sources: usize) -> bool {
+    args.recursive
+        || args.archive
+        || args.chunk_size.is_some()
+        || args.best
+        || args.format != Format::Text
+        || sources > 1
+        || (args.filter_min_length.is_some() && options.delimiter.is_some())
+}
+
+/// logs the messages up to the level given by the number of `-v`, or no messages at all if `quiet`, unless `RUST_LOG` says otherwise
+fn init_logging(verbose: u8, quiet: bool) {
+    let level = match (quiet, verbose) {
+        (true, _) => log::LevelFilter::Off,
+        (false, 0) => log::LevelFilter::Warn,
+        (false, 1) => log::LevelFilter::Info,
+        (false, 2) => log::LevelFilter::Debug,
+        (false, _) => log::LevelFilter::Trace,
+    };
+    // only the messages of this crate, not those of the decompressors or the HTTP client
+    env_logger::Builder::new()
+        .filter_module("longestlyndonsubseq", level)
+        .parse_default_env()
+        .init();
+}
+
+/// the longest Lyndon subsequence of `text` as found by `BatchSolver`, logging the search of each starting character and showing it in a progress bar labeled with `label` if `bar`
+fn solve_reporting(text: &[u8], label: &str, bar: bool) -> LyndonResult {
+    let started = Instant::now();
+    let bar = match bar {
+        true => ProgressBar::new(0),
+        false => ProgressBar::hidden(),
+    };
+    bar.set_style(
+        ProgressStyle::with_template(
+            "{prefix} [{bar:30}] {pos}/{len} starting characters, {msg}, ETA {eta}",
+        )
+        .expect("the template is valid")
+        .progress_chars("=> "),
+    );
+    bar.set_prefix(label.to_owned());
+    let mut current = None;
+    // the log needs every starting character, the progress bar is redrawn at most 20 times a second anyway
+    let interval = match log::log_enabled!(log::Level::Trace) {
+        true => 1,
+        false => 1 << 12,
+    };
+    let result = longest_lyndon_subsequence_with_progress(text, interval, |progress| {
+        bar.set_length(progress.distinct_characters as u64);
+        bar.set_position(progress.completed_characters as u64);
+        bar.set_message(format!(
+            "{} positions visited, longest {}",
+            progress.steps, progress.best_len
+        ));
+        if current != Some(progress.starting_character) {
+            current = Some(progress.starting_character);
+            log::trace!(
+                "searching the subsequences starting with {:?} ({} of {}), the longest so far has length {}, after {:?}",
+                char::from(progress.starting_character),
+                progress.completed_characters + 1,
+                progress.distinct_characters,
+                progress.best_len,
+                started.elapsed()
+            );
+        }
+    });
+    bar.finish_and_clear();
+    result
+}
+
+/// checks that the positions of `result` increase, that the characters of `record` at them are the subsequence of `result`, and that it is a Lyndon word in the `order`
+fn verify(order: &Order, record: &Record, result: &Solution) -> Result<()> {
+    let positions = match result.positions() {
+        Some(positions) => positions,
+        None => bail!("only subsequences of bytes and characters can be verified"),
+    };
+    if let Some(pair) = positions.windows(2).find(|pair| pair[0] >= pair[1]) {
+        bail!("the positions {} and {} do not increase", pair[0], pair[1]);
+    }
+    let chars: Vec<char> = match result {
+        Solution::Chars(_) => std::str::from_utf8(&record.text)?.chars().collect(),
+        _ => record.text.iter().map(|&b| b as char).collect(),
+    };
+    let subsequence = positions
+        .iter()
+        .map(|&i| chars.get(i).copied())
+        .collect::<Option<Vec<char>>>()
+        .with_context(|| {
+            format!(
+                "the positions exceed the text of {} characters",
+                chars.len()
+            )
+        })?;
+    let bytes: Vec<u8> = match result {
+        Solution::Chars(_) => subsequence.iter().collect::<String>().into_bytes(),
+        _ => subsequence.iter().map(|&c| c as u8).collect(),
+    };
+    if result.len() != positions.len() || result.bytes().as_deref() != Some(&bytes[..]) {
+        bail!("the characters at the positions are not the subsequence");
+    }
+    let keys: Vec<usize> = subsequence.iter().map(|&c| order.key(c)).collect();
+    if !keys.is_empty() && !is_lyndon(&keys) {
+        bail!("the subsequence is not a Lyndon word");
+    }
+    Ok(())
+}
+
+/// computes the longest Lyndon subsequence of the characters of `record` kept by `filter` in the `order`, with its positions in the whole text of `record`
+fn solve(
+    args: &Args,
+    filter: &CharFilter,
+    order: &Order,
+    solver: &mut BatchSolver,
+    record: &Record,
+) -> Result<Solution> {
+    let solution = solve_filtered(args, filter, order, solver, record)?;
+    if args.length_only {
+        return Ok(Solution::Length(solution.len()));
+    }
+    unfilter(filter, record, solution)
+}
+
+/// the number of prefixes that `per_prefix` takes without a stride
+const PER_PREFIX_POINTS: usize = 100;
+
+/// the lengths `end` of every `stride`-th prefix of `keys` and of `keys` itself, each with the length of a longest Lyndon subsequence of that prefix
+///
+/// Each prefix is searched on its own, so the stride defaults to the length of `keys` divided by `PER_PREFIX_POINTS`.
+fn per_prefix(keys: &[usize], stride: Option<usize>) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let stride = stride.unwrap_or_else(|| keys.len().div_ceil(PER_PREFIX_POINTS).max(1));
+    let mut ends: Vec<usize> = (stride..=keys.len()).step_by(stride).collect();
+    if ends.last() != Some(&keys.len()) && !keys.is_empty() {
+        ends.push(keys.len());
+    }
+    ends.into_iter()
+        .map(|end| (end, longest_lyndon_subsequence(&keys[..end]).len()))
+}
+
+/// computes the `k` longest distinct Lyndon subsequences of the characters of `record` kept by `filter` in the `order`, with their positions in the whole text of `record`
+fn solve_top_k(
+    args: &Args,
+    filter: &CharFilter,
+    order: &Order,
+    record: &Record,
+    k: usize,
+) -> Result<Vec<Solution>> {
+    let solutions: Vec<Solution> = if args.unicode {
+        let text = std::str::from_utf8(&record.text)
+            .with_context(|| format!("{} is not valid UTF-8", record.label()))?;
+        let text = &*filter.chars(text);
+        order
+            .check(text.chars())
+            .with_context(|| format!("cannot process {}", record.label()))?;
+        let chars: Vec<char> = text.chars().collect();
+        top_k_longest_by_key(&chars, k, |&c| order.key(c))
+            .into_iter()
+            .map(Solution::Chars)
+            .collect()
+    } else {
+        let text = &*filter.bytes(&record.text);
+        let results = if order.is_natural() {
+            top_k_longest(text, k)
+        } else {
+            order
+                .check(text.iter().map(|&byte| char::from(byte)))
+                .with_context(|| format!("cannot process {}", record.label()))?;
+            top_k_longest_by_key(text, k, |&byte| order.key(byte.into()))
+        };
+        results.into_iter().map(Solution::Bytes).collect()
+    };
+    solutions
+        .into_iter()
+        .map(|solution| unfilter(filter, record, solution))
+        .collect()
+}
+
+/// maps the positions of `solution` in the characters of `record` kept by `filter` to positions in its whole text
+fn unfilter(filter: &CharFilter, record: &Record, solution: Solution) -> Result<Solution> {
+    let kept = match solution {
+        Solution::Bytes(_) => filter.kept_bytes(&record.text),
+        // the text has been checked to be UTF-8
+        Solution::Chars(_) => filter.kept_chars(std::str::from_utf8(&record.text)?),
+        Solution::Graphemes { .. } | Solution::Tokens { .. } | Solution::Length(_) => None,
+    };
+    Ok(match kept {
+        Some(kept) => solution.map_positions(|pos| kept[pos]),
+        None => solution,
+    })
+}
+
+/// computes the longest Lyndon subsequence of the characters of `record` kept by `filter` in the `order`, with the symbols selected by `args`
+fn solve_filtered(
+    args: &Args,
+    filter: &CharFilter,
+    order: &Order,
+    solver: &mut BatchSolver,
+    record: &Record,
+) -> Result<Solution> {
+    if args.unicode || args.graphemes {
+        let text = std::str::from_utf8(&record.text)
+            .with_context(|| format!("{} is not valid UTF-8", record.label()))?;
+        let started = Instant::now();
+        let kept = filter.chars(text);
+        log::debug!(
+            "filtered {}: kept {} of {} bytes in {:?}",
+            record.label(),
+            kept.len(),
+            text.len(),
+            started.elapsed()
+        );
+        let text = &*kept;
+        return Ok(if args.graphemes {
+            let collation = match args.collation {
+                Collation::Codepoint if args.ignore_case => Collation::Caseless,
+                collation => collation,
+            };
+            let (subsequence, len) = symbols::longest_lyndon_subsequence_graphemes(text, collation);
+            Solution::Graphemes { subsequence, len }
+        } else if order.is_natural() {
+            #[cfg(feature = "rayon")]
+            if args.threads != 1 {
+                let chars: Vec<char> = text.chars().collect();
+                return Ok(Solution::Chars(
+                    longestlyndonsubseq::par_longest_lyndon_subsequence(&chars),
+                ));
+            }
+            Solution::Chars(longest_lyndon_subsequence_chars(text))
+        } else {
+            order
+                .check(text.chars())
+                .with_context(|| format!("cannot process {}", record.label()))?;
+            let chars: Vec<char> = text.chars().collect();
+            Solution::Chars(longest_lyndon_subsequence_by_key(&chars, |&c| order.key(c)))
+        });
+    }
+    let started = Instant::now();
+    let text = &*filter.bytes(&record.text);
+    log::debug!(
+        "filtered {}: kept {} of {} bytes in {:?}",
+        record.label(),
+        text.len(),
+        record.text.len(),
+        started.elapsed()
+    );
+    Ok(if args.tokens {
+        let (subsequence, len) = symbols::longest_lyndon_subsequence_tokens(text, args.ignore_case);
+        Solution::Tokens { subsequence, len }
+    } else if order.is_natural() {
+        #[cfg(feature = "rayon")]
+        if args.threads != 1 {
+            return Ok(Solution::Bytes(
+                longestlyndonsubseq::par_longest_lyndon_subsequence(text),
+            ));
+        }
+        if args.length_only {
+            return Ok(Solution::Length(longest_lyndon_subsequence_len(text)));
+        }
+        // texts that are solved in the blink of an eye need no progress bar
+        let bar = !args.quiet && text.len() >= PROGRESS_MIN_LEN && io::stderr().is_terminal();
+        if bar || log::log_enabled!(log::Level::Trace) {
+            return Ok(Solution::Bytes(solve_reporting(text, &record.label(), bar)));
+        }
+        Solution::Bytes(solver.solve(text))
+    } else {
+        let chars = || text.iter().map(|&byte| char::from(byte));
+        order
+            .check(chars())
+            .with_context(|| format!("cannot process {}", record.label()))?;
+        let keys: Vec<usize> = (0..=u8::MAX).map(|byte| order.key(byte.into())).collect();
+        let cmp = |a: &u8, b: &u8| keys[usize::from(*a)].cmp(&keys[usize::from(*b)]);
+        Solution::Bytes(Solver::new().order(cmp).solve(text))
+    })
+}
+
+/// a longest Lyndon subsequence of the bytes, the codepoints, the grapheme clusters, or the words of a text
+#[derive(serde::Serialize, serde::Deserialize)]
+enum Solution {
+    Bytes(LyndonResult),
+    Chars(LyndonResult<char>),
+    Graphemes {
+        subsequence: String,
+        len: usize,
+    },
+    /// the words separated by spaces
+    Tokens {
+        subsequence: Vec<u8>,
+        len: usize,
+    },
+    /// only the length, see `--length-only`
+    Length(usize),
+}
+
+impl Solution {
+    fn len(&self) -> usize {
+        match self {
+            Solution::Bytes(result) => result.len(),
+            Solution::Chars(result) => result.len(),
+            Solution::Graphemes { len, .. } | Solution::Tokens { len, .. } => *len,
+            Solution::Length(len) => *len,
+        }
+    }
+
+    /// the positions of the subsequence in the text, if the symbols are bytes or characters
+    fn positions(&self) -> Option<&[usize]> {
+        match self {
+            Solution::Bytes(result) => Some(result.positions()),
+            Solution::Chars(result) => Some(result.positions()),
+            Solution::Graphemes { .. } | Solution::Tokens { .. } | Solution::Length(_) => None,
+        }
+    }
+
+    /// how the search composed the subsequence, if the symbols are bytes or characters and it is not empty
+    fn structure(&self) -> Option<LyndonStructure> {
+        if self.len() == 0 {
+            return None;
+        }
+        match self {
+            Solution::Bytes(result) => Some(result.structure()),
+            Solution::Chars(result) => Some(result.structure()),
+            Solution::Graphemes { .. } | Solution::Tokens { .. } | Solution::Length(_) => None,
+        }
+    }
+
+    /// the same subsequence at the positions `f(pos)`
+    fn map_positions(self, f: impl FnMut(usize) -> usize) -> Self {
+        match self {
+            Solution::Bytes(result) => Solution::Bytes(result.map_positions(f)),
+            Solution::Chars(result) => Solution::Chars(result.map_positions(f)),
+            solution => solution,
+        }
+    }
+
+    /// the bytes of the subsequence, or `None` if only the length is known
+    fn bytes(&self) -> Option<Cow<'_, [u8]>> {
+        Some(match self {
+            Solution::Bytes(result) => result.as_bytes().into(),
+            Solution::Chars(result) => result.to_string().into_bytes().into(),
+            Solution::Graphemes { subsequence, .. } => subsequence.as_bytes().into(),
+            Solution::Tokens { subsequence, .. } => subsequence.into(),
+            Solution::Length(_) => return None,
+        })
+    }
+
+    /// the subsequence as a string, escaped by [`output::escape`] if its bytes are not UTF-8, or `None` if only the length is known
+    fn subsequence(&self) -> Option<Cow<'_, str>> {
+        let lossless = |bytes| match std::str::from_utf8(bytes) {
+            Ok(subsequence) => Cow::Borrowed(subsequence),
+            Err(_) => Cow::Owned(output::escape(bytes)),
+        };
+        Some(match self {
+            Solution::Bytes(result) => lossless(result.subsequence()),
+            Solution::Chars(result) => result.to_string().into(),
+            Solution::Graphemes { subsequence, .. } => subsequence.into(),
+            Solution::Tokens { subsequence, .. } => lossless(subsequence),
+            Solution::Length(_) => return None,
+        })
+    }
+}
+
+/// the characters of a text kept by a filter, each with the key by which it compares
+struct Symbols {
+    text: Vec<u8>,
+    /// the `i`-th symbol is `text[bounds[i]..bounds[i + 1]]`
+    bounds: Vec<usize>,
+    keys: Vec<usize>,
+}
+
+impl Symbols {
+    /// the bytes, or the characters if `--unicode`, of `record` kept by `filter`, compared in the `order`
+    fn new(args: &Args, filter: &CharFilter, order: &Order, record: &Record) -> Result<Self> {
+        if args.graphemes || args.tokens {
+            bail!("only bytes and characters can be factorized, checked or enumerated");
+        }
+        if !args.unicode {
+            let text = filter.bytes(&record.text).into_owned();
+            order.check(text.iter().map(|&b| b as char))?;
+            return Ok(Symbols {
+                bounds: (0..=text.len()).collect(),
+                keys: text.iter().map(|&b| order.key(b as char)).collect(),
+                text,
+            });
+        }
+        let text = std::str::from_utf8(&record.text)
+            .with_context(|| format!("{} is not valid UTF-8", record.label()))?;
+        let text = filter.chars(text).into_owned();
+        order.check(text.chars())?;
+        let mut bounds: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+        bounds.push(text.len());
+        Ok(Symbols {
+            keys: text.chars().map(|c| order.key(c)).collect(),
+            bounds,
+            text: text.into_bytes(),
+        })
+    }
+
+    /// the bytes of the symbols in `range`
+    fn get(&self, range: Range<usize>) -> &[u8] {
+        &self.text[self.bounds[range.start]..self.bounds[range.end]]
+    }
+}
+
+#[test]
+fn test_completions() {
+    Cli::command().debug_assert();
+    let mut script = Vec::new();
+    clap_complete::generate(Shell::Bash, &mut Cli::command(), "lls", &mut script);
+    let script = String::from_utf8(script).unwrap();
+    assert!(script.contains("--alphabet") && script.contains("completions"));
+}
+
+#[test]
+fn test_symbols() {
+    let args = Cli::parse_from(["lls", "-u", "--alphabet", "cbä"]).args;
+    let order = Order::new(false, true, args.alphabet.clone(), args.unlisted, false);
+    let record = Record {
+        input: "text".to_owned(),
+        name: None,
+        text: "bcäb".as_bytes().to_vec().into(),
+        range: None,
+        start: 0,
+    };
+    let symbols = Symbols::new(&args, &CharFilter::default(), &order, &record).unwrap();
+    let factors: Vec<&[u8]> = lyndon_factorization(&symbols.keys)
+        .into_iter()
+        .map(|range| symbols.get(range))
+        .collect();
+    assert_eq!(factors, ["b".as_bytes(), "cäb".as_bytes()]);
+    assert!(!is_lyndon(&symbols.keys));
+}
+
+#[test]
+fn test_parse_range() {
+    assert_eq!(parse_range("1000..5000").unwrap(), 1000..5000);
+    assert_eq!(parse_range("3..3").unwrap(), 3..3);
+    assert!(parse_range("5..3").is_err());
+    assert!(parse_range("5").is_err());
+    assert!(parse_range("a..3").is_err());
+}
+
+#[test]
+fn test_labeled() {
+    let options = |delimiter| InputOptions {
+        delimiter,
+        ..Default::default()
+    };
+    let args = |argv: &[&str]| Cli::parse_from(argv).args;
+    assert!(!labeled(
+        &args(&["lls", "--lines"]),
+        &options(Some(b'\n')),
+        1
+    ));
+    assert!(labeled(&args(&["lls"]), &options(None), 2));
+    let filtered = args(&["lls", "--lines", "--filter-min-length", "3"]);
+    assert!(labeled(&filtered, &options(Some(b'\n')), 1));
+    assert!(!labeled(&filtered, &options(None), 1));
+}
+
+#[test]
+fn test_per_prefix() {
+    let keys: Vec<usize> = b"bccadbaccbcd".iter().map(|&b| b.into()).collect();
+    let lengths: Vec<usize> = per_prefix(&keys, Some(1))
+        .map(|(_, length)| length)
+        .collect();
+    assert_eq!(lengths, [1, 2, 3, 3, 4, 4, 4, 5, 6, 6, 7, 9]);
+    let strided: Vec<(usize, usize)> = per_prefix(&keys, Some(5)).collect();
+    assert_eq!(strided, [(5, 4), (10, 6), (12, 9)]);
+    assert_eq!(per_prefix(&keys, None).count(), keys.len());
+    assert_eq!(per_prefix(&[], Some(3)).count(), 0);
+    // without a stride, a text of a few KiB takes a hundred searches, not thousands
+    let keys = vec![0; 4100];
+    let ends: Vec<usize> = per_prefix(&keys, None).map(|(end, _)| end).collect();
+    assert_eq!(ends.len(), PER_PREFIX_POINTS);
+    assert_eq!(ends[..2], [41, 82]);
+    assert_eq!(ends.last(), Some(&4100));
+}
+
+#[test]
+fn test_verify() {
+    let order = Order::new(false, false, None, Unlisted::Error, false);
+    let record = Record {
+        input: "text".to_owned(),
+        name: None,
+        text: b"bcab".to_vec().into(),
+        range: None,
+        start: 0,
+    };
+    let result = longestlyndonsubseq::longest_lyndon_subsequence_bytes(b"bcab");
+    assert!(verify(&order, &record, &Solution::Bytes(result.clone())).is_ok());
+    let shifted = Solution::Bytes(result.clone().map_positions(|i| i - 2));
+    assert!(verify(&order, &record, &shifted).is_err());
+    let reversed = Order::new(false, false, None, Unlisted::Error, true);
+    assert!(verify(&reversed, &record, &Solution::Bytes(result)).is_err());
+    assert!(verify(&order, &record, &Solution::Length(2)).is_err());
+}
@@ -0,0 +1,1006 @@
+use std::borrow::Cow;
+use std::io::Write;
+use std::ops::Range;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::input::Record;
+use crate::Solution;
+
+/// how the results are written
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// the subsequence, preceded by the label of the text and a tab if there are several texts
+    Text,
+    /// an object with the length, positions, subsequence, label and computation time, or an array of those if there are several texts
+    Json,
+    /// a JSON object per line, written as soon as the result of a text is known
+    Ndjson,
+    /// comma-separated values with a header, in the columns `input,record,length,positions,subsequence`
+    Csv,
+    /// like `csv` with tabs instead of commas
+    Tsv,
+    /// the objects of `json` in MessagePack, one after another, if built with the feature `msgpack`
+    Msgpack,
+    /// the objects of `json` in CBOR, one after another, if built with the feature `cbor`
+    Cbor,
+    /// the runs of adjacent positions of each subsequence as BED intervals, on the sequence named by the record, e.g., the FASTA identifier
+    Bed,
+    /// the runs of adjacent positions of each subsequence as GFF3 features, like `bed`
+    Gff,
+}
+
+/// when `OutputFormat::Text` escapes the bytes of the subsequences that are not printable UTF-8
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Escape {
+    /// if the standard output is a terminal, while pipes and files get the raw bytes
+    Auto,
+    /// also in pipes
+    Always,
+    /// also on a terminal
+    Never,
+}
+
+/// how the positions of a subsequence are written
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PositionEncoding {
+    /// every position, e.g., `0 2 3 4`
+    List,
+    /// the runs of adjacent positions as their first and last position, e.g., `0 2-4`, or `[[0,0],[2,4]]` in JSON
+    Runs,
+    /// the first position and then the differences to the previous one, e.g., `0 2 1 1`
+    Delta,
+}
+
+/// the names of the fields describing the structure of a subsequence, see `Output::with_structure`
+const STRUCTURE: [&str; 5] = [
+    "period",
+    "root_period",
+    "exponent",
+    "immature_start",
+    "power_prefix",
+];
+
+/// the longest text that `Output::with_highlight` and `Output::with_alignment` write, longer ones would not fit on a screen anyway
+const DISPLAY_LIMIT: usize = 1 << 16;
+
+/// the terminal escape sequences around the highlighted characters, bold, underlined and red
+const HIGHLIGHT: (&str, &str) = ("\x1b[1;4;31m", "\x1b[0m");
+
+/// the subsequence of `result` as a string, in hexadecimal digits if `hex`, or `None` if only the length is known
+///
+/// Subsequences whose bytes are not UTF-8 are written with [`escape`].
+fn subsequence(result: &Solution, hex: bool) -> Option<String> {
+    if hex {
+        return result.bytes().map(|bytes| to_hex(&bytes));
+    }
+    result.subsequence().map(Cow::into_owned)
+}
+
+/// the JSON object describing the `result` for the text with `label` computed in `elapsed`, with the subsequence in hexadecimal digits if `hex`
+pub fn to_json(label: &str, result: &Solution, elapsed: Duration, hex: bool) -> Value {
+    json!({
+        "length": result.len(),
+        "positions": result.positions(),
+        "subsequence": subsequence(result, hex),
+        "input": label,
+        "elapsed_ms": elapsed.as_secs_f64() * 1000.0,
+    })
+}
+
+/// an object of `to_json` for the binary formats, with the subsequence of bytes as a byte string instead of an escaped string
+struct BinaryObject<'a> {
+    object: &'a Value,
+    /// replaces the field `subsequence`, if the subsequence consists of bytes
+    subsequence: Option<&'a [u8]>,
+}
+
+impl serde::Serialize for BinaryObject<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        /// serializes as a byte string, e.g., `bin` in MessagePack
+        struct Bytes<'a>(&'a [u8]);
+
+        impl serde::Serialize for Bytes<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_bytes(self.0)
+            }
+        }
+
+        let Value::Object(fields) = self.object else {
+            return self.object.serialize(serializer);
+        };
+        let mut map = serializer.serialize_map(Some(fields.len()))?;
+        for (key, value) in fields {
+            match self.subsequence.filter(|_| key == "subsequence") {
+                Some(bytes) => map.serialize_entry(key, &Bytes(bytes))?,
+                None => map.serialize_entry(key, value)?,
+            }
+        }
+        map.end()
+    }
+}
+
+/// writes the results of the texts one after another
+pub struct Output<W: Write> {
+    out: W,
+    format: OutputFormat,
+    /// whether the results need the labels of their texts
+    labeled: bool,
+    /// whether there are several results, which `OutputFormat::Json` writes as an array
+    several: bool,
+    /// the objects of `OutputFormat::Json` written by `finish`
+    objects: Vec<Value>,
+    /// whether the header of `OutputFormat::Csv`, `OutputFormat::Tsv` or `OutputFormat::Gff` has been written
+    header: bool,
+    /// whether `OutputFormat::Text` also writes the positions
+    positions: bool,
+    /// whether `OutputFormat::Text` writes a single unlabeled subsequence without a line break
+    raw: bool,
+    /// whether `OutputFormat::Text` writes bytes that are not printable UTF-8 as `\xNN`
+    escape: bool,
+    /// whether the subsequences are written as hexadecimal digits
+    hex: bool,
+    /// whether `OutputFormat::Text` writes the texts with their subsequences highlighted instead
+    highlight: bool,
+    /// whether the formats but `OutputFormat::Text`, `OutputFormat::Bed` and `OutputFormat::Gff` describe where the texts come from
+    provenance: bool,
+    /// whether the formats but `OutputFormat::Bed` and `OutputFormat::Gff` also describe the structure of the subsequences
+    structure: bool,
+    /// the size of the source of the following texts, for their provenance
+    source_size: Option<u64>,
+    /// how the positions are written
+    position_encoding: PositionEncoding,
+    /// the width of the rows in which `OutputFormat::Text` writes the texts with markers under their subsequences instead
+    alignment: Option<usize>,
+}
+
+impl<W: Write> Output<W> {
+    pub fn new(out: W, format: OutputFormat, labeled: bool, several: bool) -> Self {
+        Output {
+            out,
+            format,
+            labeled,
+            several,
+            objects: Vec::new(),
+            header: false,
+            positions: false,
+            raw: false,
+            escape: false,
+            hex: false,
+            highlight: false,
+            alignment: None,
+            position_encoding: PositionEncoding::List,
+            provenance: false,
+            structure: false,
+            source_size: None,
+        }
+    }
+
+    /// writes the bytes of the subsequences as pairs of hexadecimal digits in all formats, which takes precedence over [`with_raw`](Self::with_raw)
+    pub fn with_hex(mut self, hex: bool) -> Self {
+        self.hex = hex;
+        self
+    }
+
+    /// writes only the bytes of the subsequence in the format `OutputFormat::Text` if there is a single unlabeled text
+    pub fn with_raw(mut self, raw: bool) -> Self {
+        self.raw = raw;
+        self
+    }
+
+    /// writes the bytes of the subsequences and lines in the format `OutputFormat::Text` that are not printable UTF-8 as `\xNN`, and backslashes as `\\`, instead of as they are
+    pub fn with_escape(mut self, escape: bool) -> Self {
+        self.escape = escape;
+        self
+    }
+
+    /// writes the texts in the format `OutputFormat::Text` with the characters of their subsequences highlighted instead of the subsequences
+    pub fn with_highlight(mut self, highlight: bool) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    /// writes the texts in the format `OutputFormat::Text` in rows of at most `width` characters, each followed by a row with `^` under the characters of the subsequence, instead of the subsequences
+    pub fn with_alignment(mut self, width: Option<usize>) -> Self {
+        self.alignment = width;
+        self
+    }
+
+    /// also writes the size of the source, the positions of each text in it, and the SHA-256 hash of the text, in all formats but `OutputFormat::Text`, `OutputFormat::Bed` and `OutputFormat::Gff`
+    pub fn with_provenance(mut self, provenance: bool) -> Self {
+        self.provenance = provenance;
+        self
+    }
+
+    /// also writes the period of each subsequence and the decomposition of it without its last character into a power of a Lyndon word and an immature prefix, in all formats but `OutputFormat::Bed` and `OutputFormat::Gff`
+    pub fn with_structure(mut self, structure: bool) -> Self {
+        self.structure = structure;
+        self
+    }
+
+    /// sets the size in bytes of the source of the following texts, if it is known
+    pub fn set_source_size(&mut self, size: Option<u64>) {
+        self.source_size = size;
+    }
+
+    /// writes the positions of the subsequences in the `encoding` in all formats but `OutputFormat::Bed` and `OutputFormat::Gff`
+    pub fn with_position_encoding(mut self, encoding: PositionEncoding) -> Self {
+        self.position_encoding = encoding;
+        self
+    }
+
+    /// also writes the positions of the subsequences in the format `OutputFormat::Text`, the other formats always contain them
+    pub fn with_positions(mut self, positions: bool) -> Self {
+        self.positions = positions;
+        self
+    }
+
+    /// writes the `result` for the text of `record` computed in `elapsed`
+    pub fn write_result(
+        &mut self,
+        record: &Record,
+        result: &Solution,
+        elapsed: Duration,
+    ) -> Result<()> {
+        let label = &record.label();
+        // the positions in the whole source or sequence, not in the window of the text
+        let positions: Option<Vec<usize>> = result
+            .positions()
+            .map(|positions| positions.iter().map(|&pos| record.start + pos).collect());
+        let positions = positions.as_deref();
+        match self.format {
+            OutputFormat::Text if self.alignment.is_some() => {
+                let width = self.alignment.unwrap_or_default();
+                self.write_alignment(record, result, width)
+            }
+            OutputFormat::Text => {
+                let subsequence = match (self.highlight, self.hex) {
+                    (true, _) => Some(self.highlighted(record, result)?),
+                    (false, true) => subsequence(result, true).map(String::into_bytes),
+                    (false, false) => result
+                        .bytes()
+                        .map(|bytes| self.escaped(&bytes).into_owned()),
+                };
+                let mut line = subsequence.unwrap_or_else(|| result.len().to_string().into_bytes());
+                if let Some(positions) = positions.filter(|_| self.positions) {
+                    line.push(b'\t');
+                    line.extend_from_slice(join(positions, self.position_encoding).as_bytes());
+                }
+                if let Some(structure) = self.structure(result) {
+                    let fields: Vec<String> = STRUCTURE
+                        .iter()
+                        .zip(structure)
+                        .map(|(key, value)| format!("{key}={value}"))
+                        .collect();
+                    line.push(b'\t');
+                    line.extend_from_slice(fields.join(" ").as_bytes());
+                }
+                if self.raw && !self.labeled && !self.several && !self.positions {
+                    self.out.write_all(&line)?;
+                    return Ok(());
+                }
+                self.write_labeled(label, &line)
+            }
+            OutputFormat::Json
+            | OutputFormat::Ndjson
+            | OutputFormat::Msgpack
+            | OutputFormat::Cbor => {
+                let mut object = to_json(label, result, elapsed, self.hex);
+                if let Some(positions) = positions {
+                    object["positions"] = match self.position_encoding {
+                        PositionEncoding::List => positions.into(),
+                        PositionEncoding::Runs => runs(positions)
+                            .map(|run| [run.start, run.end - 1])
+                            .collect::<Vec<_>>()
+                            .into(),
+                        PositionEncoding::Delta => deltas(positions).collect::<Vec<_>>().into(),
+                    };
+                }
+                if let Some(structure) = self.structure(result) {
+                    object["structure"] = STRUCTURE
+                        .iter()
+                        .map(|key| key.to_string())
+                        .zip(structure)
+                        .collect();
+                }
+                if self.provenance {
+                    let [input_size, start, end, sha256] = self.provenance(record);
+                    object["provenance"] = json!({
+                        "input_size": input_size,
+                        "start": start,
+                        "end": end,
+                        "sha256": sha256,
+                    });
+                }
+                match self.format {
+                    OutputFormat::Json if self.several => {
+                        self.objects.push(object);
+                        return Ok(());
+                    }
+                    OutputFormat::Msgpack | OutputFormat::Cbor => {
+                        let bytes = match result {
+                            Solution::Bytes(_) | Solution::Tokens { .. } if !self.hex => {
+                                result.bytes()
+                            }
+                            _ => None,
+                        };
+                        self.write_binary(&BinaryObject {
+                            object: &object,
+                            subsequence: bytes.as_deref(),
+                        })?
+                    }
+                    _ => {
+                        serde_json::to_writer(&mut self.out, &object)?;
+                        writeln!(self.out)?;
+                    }
+                }
+                // downstream consumers should not wait for the next result
+                self.out.flush()?;
+                Ok(())
+            }
+            OutputFormat::Csv | OutputFormat::Tsv => {
+                let separator = match self.format {
+                    OutputFormat::Tsv => '\t',
+                    _ => ',',
+                };
+                if !self.header {
+                    self.header = true;
+                    let mut header = vec!["input", "record", "length", "positions", "subsequence"];
+                    if self.structure {
+                        header.extend(STRUCTURE);
+                    }
+                    if self.provenance {
+                        header.extend(["input_size", "start", "end", "sha256"]);
+                    }
+                    writeln!(self.out, "{}", header.join(&separator.to_string()))?;
+                }
+                let positions = positions.map(|positions| join(positions, self.position_encoding));
+                let mut fields = vec![
+                    record.input.clone(),
+                    record.name.clone().unwrap_or_default(),
+                    result.len().to_string(),
+                    positions.unwrap_or_default(),
+                    subsequence(result, self.hex).unwrap_or_default(),
+                ];
+                if self.structure {
+                    match self.structure(result) {
+                        Some(structure) => fields.extend(structure.map(|value| value.to_string())),
+                        None => fields.extend(STRUCTURE.map(|_| String::new())),
+                    }
+                }
+                if self.provenance {
+                    let unknown = |value: Value| match value {
+                        Value::Null => String::new(),
+                        Value::String(value) => value,
+                        value => value.to_string(),
+                    };
+                    fields.extend(self.provenance(record).map(unknown));
+                }
+                let fields: Vec<String> =
+                    fields.iter().map(|field| quote(field, separator)).collect();
+                writeln!(self.out, "{}", fields.join(&separator.to_string()))?;
+                Ok(())
+            }
+            OutputFormat::Bed | OutputFormat::Gff => {
+                self.write_intervals(record, result, positions)
+            }
+        }
+    }
+
+    /// writes `object` in the binary `OutputFormat::Msgpack` or `OutputFormat::Cbor`
+    #[cfg_attr(
+        not(all(feature = "msgpack", feature = "cbor")),
+        allow(unused_variables)
+    )]
+    fn write_binary(&mut self, object: &BinaryObject) -> Result<()> {
+        match self.format {
+            #[cfg(feature = "msgpack")]
+            OutputFormat::Msgpack => Ok(rmp_serde::encode::write(&mut self.out, object)?),
+            #[cfg(not(feature = "msgpack"))]
+            OutputFormat::Msgpack => {
+                bail!("cannot write MessagePack, rebuild with the feature `msgpack`")
+            }
+            #[cfg(feature = "cbor")]
+            OutputFormat::Cbor => Ok(ciborium::into_writer(object, &mut self.out)?),
+            #[cfg(not(feature = "cbor"))]
+            OutputFormat::Cbor => bail!("cannot write CBOR, rebuild with the feature `cbor`"),
+            format => unreachable!("{format:?} is not a binary format"),
+        }
+    }
+
+    /// writes the runs of adjacent `positions` of `result` as intervals on the sequence of `record`, with the characters of the runs as their names
+    fn write_intervals(
+        &mut self,
+        record: &Record,
+        result: &Solution,
+        positions: Option<&[usize]>,
+    ) -> Result<()> {
+        let Some(positions) = positions else {
+            bail!("only subsequences of bytes and characters can be written as intervals");
+        };
+        if self.format == OutputFormat::Gff && !self.header {
+            self.header = true;
+            writeln!(self.out, "##gff-version 3")?;
+        }
+        let sequence = record.name.as_ref().unwrap_or(&record.input);
+        // the bytes of the characters of the subsequence
+        let symbols: Vec<Vec<u8>> = match result {
+            Solution::Chars(result) => result
+                .subsequence()
+                .iter()
+                .map(|c| c.to_string().into_bytes())
+                .collect(),
+            _ => result
+                .bytes()
+                .unwrap_or_default()
+                .chunks(1)
+                .map(<[u8]>::to_vec)
+                .collect(),
+        };
+        let mut symbols = symbols.into_iter();
+        for run in runs(positions) {
+            let name: Vec<u8> = symbols.by_ref().take(run.len()).flatten().collect();
+            let name = escape(&name);
+            match self.format {
+                OutputFormat::Bed => {
+                    writeln!(self.out, "{sequence}\t{}\t{}\t{name}", run.start, run.end)?
+                }
+                _ => writeln!(
+                    self.out,
+                    "{}\tlongestlyndonsubseq\tsequence_feature\t{}\t{}\t.\t+\t.\tName={}",
+                    percent_encode(sequence),
+                    run.start + 1,
+                    run.end,
+                    percent_encode(&name)
+                )?,
+            }
+        }
+        Ok(())
+    }
+
+    /// the values of the fields `STRUCTURE` describing the subsequence of `result`, if requested and known, i.e., not for an empty one
+    fn structure(&self, result: &Solution) -> Option<[Value; 5]> {
+        let structure = result.structure().filter(|_| self.structure)?;
+        Some([
+            result.len().into(),
+            structure.root_period.into(),
+            structure.exponent.into(),
+            structure.immature_start.into(),
+            structure.is_power_prefix(result.len()).into(),
+        ])
+    }
+
+    /// the size of the source, the start and end of the text of `record` in it, and the SHA-256 hash of the text, `null` if unknown
+    fn provenance(&self, record: &Record) -> [Value; 4] {
+        let range = record.range.clone();
+        [
+            self.source_size.into(),
+            range.clone().map(|range| range.start).into(),
+            range.map(|range| range.end).into(),
+            to_hex(&Sha256::digest(&record.text[..])).into(),
+        ]
+    }
+
+    /// writes `line`, escaped if requested, preceded by `label` and a tab if the results are labeled
+    pub fn write_line(&mut self, label: &str, line: &[u8]) -> Result<()> {
+        let line = self.escaped(line).into_owned();
+        self.write_labeled(label, &line)
+    }
+
+    fn write_labeled(&mut self, label: &str, line: &[u8]) -> Result<()> {
+        if self.labeled {
+            write!(self.out, "{}\t", label)?;
+        }
+        self.out.write_all(line)?;
+        writeln!(self.out)?;
+        Ok(())
+    }
+
+    /// the text of `record` with the characters at the positions of `result` highlighted
+    fn highlighted(&self, record: &Record, result: &Solution) -> Result<Vec<u8>> {
+        let units = units(record, result, "highlight")?;
+        let mut line = Vec::with_capacity(record.text.len());
+        for run in units.chunk_by(|a, b| a.1 == b.1) {
+            let bytes: Vec<u8> = run.iter().flat_map(|unit| unit.0).copied().collect();
+            let bytes = self.escaped(&bytes);
+            match run[0].1 {
+                true => {
+                    line.extend_from_slice(HIGHLIGHT.0.as_bytes());
+                    line.extend_from_slice(&bytes);
+                    line.extend_from_slice(HIGHLIGHT.1.as_bytes());
+                }
+                false => line.extend_from_slice(&bytes),
+            }
+        }
+        Ok(line)
+    }
+
+    /// writes the text of `record` in rows of at most `width` characters, each followed by a row with `^` under the characters at the positions of `result`
+    fn write_alignment(&mut self, record: &Record, result: &Solution, width: usize) -> Result<()> {
+        let units = units(record, result, "align")?;
+        let label = record.label();
+        let width = match width {
+            0 => units.len().max(1),
+            width => width,
+        };
+        for row in units.chunks(width) {
+            let text: String = row
+                .iter()
+                .map(|(bytes, _)| match std::str::from_utf8(bytes) {
+                    Ok(c) if !c.chars().any(char::is_control) => c,
+                    _ => ".",
+                })
+                .collect();
+            let markers: String = row
+                .iter()
+                .map(|&(_, selected)| if selected { '^' } else { ' ' })
+                .collect();
+            self.write_labeled(&label, text.as_bytes())?;
+            self.write_labeled(&label, markers.trim_end().as_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn escaped<'a>(&self, bytes: &'a [u8]) -> Cow<'a, [u8]> {
+        match self.escape {
+            true => escape(bytes).into_bytes().into(),
+            false => bytes.into(),
+        }
+    }
+
+    /// writes what is left after the last text
+    pub fn finish(mut self) -> Result<()> {
+        if self.format == OutputFormat::Json && self.several {
+            serde_json::to_writer(&mut self.out, &self.objects)?;
+            writeln!(self.out)?;
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// the lowercase hexadecimal digits of `bytes`
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// the characters that the positions of `result` count in the text of `record`, each with whether it is in the subsequence, for displaying them with `what`
+fn units<'a>(record: &'a Record, result: &Solution, what: &str) -> Result<Vec<(&'a [u8], bool)>> {
+    let text = &record.text[..];
+    if text.len() > DISPLAY_LIMIT {
+        bail!(
+            "{} is too long to {what} ({} bytes, at most {DISPLAY_LIMIT})",
+            record.label(),
+            text.len()
+        );
+    }
+    let Some(positions) = result.positions() else {
+        bail!("only subsequences of bytes and characters can be displayed with --{what}");
+    };
+    let mut units: Vec<(&[u8], bool)> = match result {
+        Solution::Chars(_) => std::str::from_utf8(text)?
+            .char_indices()
+            .map(|(i, c)| (&text[i..i + c.len_utf8()], false))
+            .collect(),
+        _ => text.chunks(1).map(|byte| (byte, false)).collect(),
+    };
+    for &position in positions {
+        units[position].1 = true;
+    }
+    Ok(units)
+}
+
+/// `bytes` with the invalid UTF-8 bytes and the control characters as `\xNN`, and backslashes as `\\`
+pub fn escape(bytes: &[u8]) -> String {
+    let mut escaped = String::with_capacity(bytes.len());
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '\\' => escaped.push_str("\\\\"),
+                c if c.is_ascii_control() => escaped.push_str(&format!("\\x{:02x}", c as u8)),
+                c => escaped.push(c),
+            }
+        }
+        for byte in chunk.invalid() {
+            escaped.push_str(&format!("\\x{byte:02x}"));
+        }
+    }
+    escaped
+}
+
+/// the maximal ranges of consecutive numbers in the increasing `positions`
+fn runs(positions: &[usize]) -> impl Iterator<Item = Range<usize>> + '_ {
+    positions
+        .chunk_by(|a, b| a + 1 == *b)
+        .map(|run| run[0]..run[run.len() - 1] + 1)
+}
+
+/// `field` with the characters reserved in the columns of GFF3 percent-encoded
+fn percent_encode(field: &str) -> String {
+    let mut encoded = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            ';' | '=' | '&' | ',' | '%' | '\t' | '\n' | '\r' => {
+                encoded.push_str(&format!("%{:02X}", c as u32))
+            }
+            c => encoded.push(c),
+        }
+    }
+    encoded
+}
+
+/// the first of the increasing `positions` and then the differences to the previous one
+fn deltas(positions: &[usize]) -> impl Iterator<Item = usize> + '_ {
+    let previous = std::iter::once(0).chain(positions.iter().copied());
+    positions
+        .iter()
+        .zip(previous)
+        .map(|(position, previous)| position - previous)
+}
+
+/// `positions` in the `encoding` separated by spaces
+fn join(positions: &[usize], encoding: PositionEncoding) -> String {
+    let positions: Vec<String> = match encoding {
+        PositionEncoding::List => positions.iter().map(usize::to_string).collect(),
+        PositionEncoding::Runs => runs(positions)
+            .map(|run| match run.len() {
+                1 => run.start.to_string(),
+                _ => format!("{}-{}", run.start, run.end - 1),
+            })
+            .collect(),
+        PositionEncoding::Delta => deltas(positions).map(|delta| delta.to_string()).collect(),
+    };
+    positions.join(" ")
+}
+
+/// `field` in double quotes if it contains the `separator`, quotes, or line breaks, as in RFC 4180
+fn quote(field: &str, separator: char) -> String {
+    match field.contains([separator, '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_owned(),
+    }
+}
+
+/// the bytes written by the output created with `output` for the result `solution` of `record`
+#[cfg(test)]
+fn render_with(
+    output: impl FnOnce(&mut Vec<u8>) -> Output<&mut Vec<u8>>,
+    record: &Record,
+    solution: &Solution,
+) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut output = output(&mut out);
+    output
+        .write_result(record, solution, Duration::ZERO)
+        .unwrap();
+    output.finish().unwrap();
+    out
+}
+
+/// the output in `format` of the result `solution` of `record`, the only text
+#[cfg(test)]
+fn render(format: OutputFormat, record: &Record, solution: &Solution) -> String {
+    let out = render_with(
+        |out| Output::new(out, format, false, false),
+        record,
+        solution,
+    );
+    String::from_utf8(out).unwrap()
+}
+
+/// a record of `input` with the `text`
+#[cfg(test)]
+fn record(input: &str, name: Option<&str>, text: &[u8]) -> Record {
+    Record {
+        input: input.to_owned(),
+        name: name.map(str::to_owned),
+        text: text.to_vec().into(),
+        range: None,
+        start: 0,
+    }
+}
+
+/// the longest Lyndon subsequence of `text`
+#[cfg(test)]
+fn solve(text: &[u8]) -> Solution {
+    Solution::Bytes(longestlyndonsubseq::longest_lyndon_subsequence_bytes(text))
+}
+
+#[test]
+fn test_text() {
+    let record = record("a.txt", None, b"");
+    assert_eq!(render(OutputFormat::Text, &record, &solve(b"bcab")), "ab\n");
+    let out = render_with(
+        |out| Output::new(out, OutputFormat::Text, true, true),
+        &record,
+        &solve(b"bcab"),
+    );
+    assert_eq!(out, b"a.txt\tab\n");
+    assert_eq!(
+        render(OutputFormat::Text, &record, &Solution::Length(3)),
+        "3\n"
+    );
+}
+
+#[test]
+fn test_json() {
+    let record = record("a.txt", None, b"");
+    let result = solve(b"bcab");
+    let object =
+        r#"{"elapsed_ms":0.0,"input":"a.txt","length":2,"positions":[2,3],"subsequence":"ab"}"#;
+    assert_eq!(
+        render(OutputFormat::Json, &record, &result),
+        format!("{object}\n")
+    );
+    let several =
+        |format| render_with(|out| Output::new(out, format, true, true), &record, &result);
+    assert_eq!(
+        several(OutputFormat::Json),
+        format!("[{object}]\n").as_bytes()
+    );
+    assert_eq!(
+        several(OutputFormat::Ndjson),
+        format!("{object}\n").as_bytes()
+    );
+}
+
+#[test]
+fn test_csv() {
+    let out = render_with(
+        |out| Output::new(out, OutputFormat::Csv, true, true),
+        &record("a.txt", None, b""),
+        &solve(b"bcab"),
+    );
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "input,record,length,positions,subsequence\na.txt,,2,2 3,ab\n"
+    );
+    assert_eq!(quote("a,\"b", ','), "\"a,\"\"b\"");
+    assert_eq!(quote("a,b", '\t'), "a,b");
+}
+
+#[test]
+fn test_non_utf8() {
+    let record = record("a.bin", None, b"\x00\xff");
+    let result = solve(b"\x00\xff");
+    assert!(render(OutputFormat::Json, &record, &result).contains(r#""subsequence":"\\x00\\xff""#));
+    assert!(
+        render(OutputFormat::Ndjson, &record, &result).contains(r#""subsequence":"\\x00\\xff""#)
+    );
+    assert!(render(OutputFormat::Csv, &record, &result).ends_with("a.bin,,2,0 1,\\x00\\xff\n"));
+    assert!(render(OutputFormat::Tsv, &record, &result).ends_with("a.bin\t\t2\t0 1\t\\x00\\xff\n"));
+}
+
+#[test]
+fn test_binary() {
+    let record = record("a.txt", None, b"");
+    let binary = solve(b"\xff\x01\x80");
+    let out = render_with(
+        |out| Output::new(out, OutputFormat::Text, false, false).with_raw(true),
+        &record,
+        &binary,
+    );
+    assert_eq!(out, b"\x01\x80");
+    let out = render_with(
+        |out| Output::new(out, OutputFormat::Json, false, false).with_hex(true),
+        &record,
+        &binary,
+    );
+    assert!(String::from_utf8(out)
+        .unwrap()
+        .contains(r#""subsequence":"0180""#));
+    let out = render_with(
+        |out| Output::new(out, OutputFormat::Text, false, false).with_escape(true),
+        &record,
+        &binary,
+    );
+    assert_eq!(out, b"\\x01\\x80\n");
+    assert_eq!(escape(b"\xc3\xa4\\\t\xff"), "ä\\\\\\x09\\xff");
+}
+
+#[test]
+fn test_highlight() {
+    let out = render_with(
+        |out| Output::new(out, OutputFormat::Text, false, false).with_highlight(true),
+        &record("a.txt", None, b"bcab"),
+        &solve(b"bcab"),
+    );
+    assert_eq!(out, b"bc\x1b[1;4;31mab\x1b[0m\n");
+}
+
+#[test]
+fn test_alignment() {
+    let out = render_with(
+        |out| Output::new(out, OutputFormat::Text, false, false).with_alignment(Some(3)),
+        &record("a.txt", None, b"bcab"),
+        &solve(b"bcab"),
+    );
+    assert_eq!(out, b"bca\n  ^\nb\n^\n");
+}
+
+#[test]
+fn test_provenance() {
+    let record = Record {
+        range: Some(4..8),
+        ..record("a.txt", None, b"bcab")
+    };
+    let out = render_with(
+        |out| {
+            let mut output =
+                Output::new(out, OutputFormat::Csv, false, false).with_provenance(true);
+            output.set_source_size(Some(10));
+            output
+        },
+        &record,
+        &solve(b"bcab"),
+    );
+    let sha256 = "92e6970b7fa86e90119f7f3382c559d50f973decae02f356667e4891369d6098";
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        format!("input,record,length,positions,subsequence,input_size,start,end,sha256\na.txt,,2,2 3,ab,10,4,8,{sha256}\n")
+    );
+}
+
+#[test]
+fn test_bed() {
+    let record = record("genes.fa", Some("chr1"), b"acab");
+    let result = solve(b"acab");
+    assert_eq!(
+        render(OutputFormat::Bed, &record, &result),
+        "chr1\t0\t1\ta\nchr1\t2\t4\tab\n"
+    );
+    assert_eq!(
+        render(OutputFormat::Gff, &record, &result),
+        "##gff-version 3\nchr1\tlongestlyndonsubseq\tsequence_feature\t1\t1\t.\t+\t.\tName=a\nchr1\tlongestlyndonsubseq\tsequence_feature\t3\t4\t.\t+\t.\tName=ab\n"
+    );
+    assert_eq!(percent_encode("a;b=c"), "a%3Bb%3Dc");
+}
+
+#[test]
+fn test_record_start() {
+    // the text starts at the fifth character of the sequence
+    let record = Record {
+        start: 4,
+        ..record("genes.fa", Some("chr1"), b"acab")
+    };
+    let result = solve(b"acab");
+    assert_eq!(
+        render(OutputFormat::Bed, &record, &result),
+        "chr1\t4\t5\ta\nchr1\t6\t8\tab\n"
+    );
+    let out = render_with(
+        |out| Output::new(out, OutputFormat::Text, false, false).with_positions(true),
+        &record,
+        &result,
+    );
+    assert_eq!(out, b"aab\t4 6 7\n");
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn test_msgpack() {
+    let record = record("genes.fa", Some("chr1"), b"acab");
+    let result = solve(b"acab");
+    let mut out = Vec::new();
+    let mut output = Output::new(&mut out, OutputFormat::Msgpack, false, true);
+    for _ in 0..2 {
+        output
+            .write_result(&record, &result, Duration::ZERO)
+            .unwrap();
+    }
+    output.finish().unwrap();
+    #[derive(serde::Deserialize)]
+    struct Object {
+        positions: Vec<usize>,
+    }
+    let mut reader = &out[..];
+    for _ in 0..2 {
+        let object: Object = rmp_serde::decode::from_read(&mut reader).unwrap();
+        assert_eq!(object.positions, [0, 2, 3]);
+    }
+    assert!(reader.is_empty());
+    // the subsequence of bytes is a `bin` after its key, also if it is not UTF-8
+    let subsequence = |text: &[u8]| {
+        let out = render_with(
+            |out| Output::new(out, OutputFormat::Msgpack, false, false),
+            &record,
+            &solve(text),
+        );
+        let key = b"\xabsubsequence\xc4";
+        let at = out
+            .windows(key.len())
+            .position(|window| window == key)
+            .unwrap();
+        let len = usize::from(out[at + key.len()]);
+        out[at + key.len() + 1..][..len].to_vec()
+    };
+    assert_eq!(subsequence(b"acab"), b"aab");
+    assert_eq!(subsequence(b"\xff\x00\xfe\x01"), b"\x00\xfe\x01");
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn test_cbor() {
+    let subsequence = |text: &[u8]| {
+        let out = render_with(
+            |out| Output::new(out, OutputFormat::Cbor, false, false),
+            &record("a.bin", None, text),
+            &solve(text),
+        );
+        let object: ciborium::Value = ciborium::from_reader(&out[..]).unwrap();
+        let fields = object.into_map().unwrap();
+        let (_, subsequence) = fields
+            .into_iter()
+            .find(|(key, _)| key.as_text() == Some("subsequence"))
+            .unwrap();
+        subsequence
+    };
+    assert_eq!(
+        subsequence(b"acab"),
+        ciborium::Value::Bytes(b"aab".to_vec())
+    );
+    assert_eq!(
+        subsequence(b"\xff\x00\xfe\x01"),
+        ciborium::Value::Bytes(b"\x00\xfe\x01".to_vec())
+    );
+}
+
+#[test]
+fn test_position_encoding() {
+    let positions = [0, 2, 3, 4, 7];
+    assert_eq!(join(&positions, PositionEncoding::List), "0 2 3 4 7");
+    assert_eq!(join(&positions, PositionEncoding::Runs), "0 2-4 7");
+    assert_eq!(join(&positions, PositionEncoding::Delta), "0 2 1 1 3");
+    let out = render_with(
+        |out| {
+            Output::new(out, OutputFormat::Ndjson, false, false)
+                .with_position_encoding(PositionEncoding::Runs)
+        },
+        &record("genes.fa", Some("chr1"), b"acab"),
+        &solve(b"acab"),
+    );
+    assert!(String::from_utf8(out)
+        .unwrap()
+        .contains(r#""positions":[[0,0],[2,3]]"#));
+}
+
+#[test]
+fn test_structure() {
+    let record = record("a.txt", None, b"bccadbaccbcd");
+    let result = solve(b"bccadbaccbcd");
+    let out = render_with(
+        |out| Output::new(out, OutputFormat::Text, false, false).with_structure(true),
+        &record,
+        &result,
+    );
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "bccbccbcd\tperiod=9 root_period=3 exponent=2 immature_start=6 power_prefix=false\n"
+    );
+    let out = render_with(
+        |out| Output::new(out, OutputFormat::Json, false, false).with_structure(true),
+        &record,
+        &result,
+    );
+    assert!(String::from_utf8(out).unwrap().contains(
+        r#""structure":{"exponent":2,"immature_start":6,"period":9,"power_prefix":false,"root_period":3}"#
+    ));
+    // an empty subsequence has no structure
+    let record = self::record("a.txt", None, b"");
+    let empty = |format| {
+        let out = render_with(
+            |out| Output::new(out, format, false, false).with_structure(true),
+            &record,
+            &solve(b""),
+        );
+        String::from_utf8(out).unwrap()
+    };
+    assert_eq!(empty(OutputFormat::Text), "\n");
+    assert!(!empty(OutputFormat::Json).contains("structure"));
+    assert!(empty(OutputFormat::Csv).ends_with("\na.txt,,0,,,,,,,\n"));
+}
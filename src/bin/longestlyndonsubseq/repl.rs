@@ -0,0 +1,220 @@
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Write};
+
+use anyhow::{bail, Result};
+
+use longestlyndonsubseq::{longest_lyndon_subsequence, lyndon_factorization};
+
+use crate::symbols::{self, Alphabet, Order, Unlisted};
+
+const HELP: &str = "\
+type a text to see its longest Lyndon subsequence and its Lyndon factorization, or a command:
+  :alphabet SPEC       compare the characters in the order of SPEC, e.g., :alphabet zyx
+  :alphabet            compare the characters by their values again
+  :unlisted error|last whether characters missing from the alphabet are an error or larger
+  :ignore-case         toggle comparing letters regardless of their case
+  :reverse             toggle inverting the order
+  :help                show this help
+  :quit                leave, like Ctrl-D";
+
+/// the order currently used by the interactive mode, changed by its commands
+pub struct Session {
+    pub ignore_case: bool,
+    pub alphabet: Option<Alphabet>,
+    pub unlisted: Unlisted,
+    pub reverse: bool,
+}
+
+/// what the interactive mode does for an input line
+#[derive(Debug, PartialEq, Eq)]
+enum Step {
+    Print(String),
+    Quit,
+}
+
+impl Session {
+    fn order(&self) -> Order {
+        Order::new(
+            self.ignore_case,
+            true,
+            self.alphabet.clone(),
+            self.unlisted,
+            self.reverse,
+        )
+    }
+
+    /// runs the command or evaluates the text `line`
+    fn handle(&mut self, line: &str) -> Result<Step> {
+        let message = |message: &str| Ok(Step::Print(message.to_owned()));
+        let command = match line.strip_prefix(':') {
+            Some(command) => command.trim(),
+            None => return self.evaluate(line).map(Step::Print),
+        };
+        let (name, argument) = command.split_once(' ').unwrap_or((command, ""));
+        match (name, argument.trim()) {
+            ("alphabet", "") => {
+                self.alphabet = None;
+                message("comparing the characters by their values")
+            }
+            ("alphabet", spec) => {
+                self.alphabet = Some(symbols::parse_alphabet(spec)?);
+                message("comparing the characters in the order of the alphabet")
+            }
+            ("unlisted", "error") => {
+                self.unlisted = Unlisted::Error;
+                message("characters missing from the alphabet are an error")
+            }
+            ("unlisted", "last") => {
+                self.unlisted = Unlisted::Last;
+                message("characters missing from the alphabet are larger")
+            }
+            ("ignore-case", "") => {
+                self.ignore_case = !self.ignore_case;
+                message(match self.ignore_case {
+                    true => "ignoring the case",
+                    false => "respecting the case",
+                })
+            }
+            ("reverse", "") => {
+                self.reverse = !self.reverse;
+                message(match self.reverse {
+                    true => "inverting the order",
+                    false => "not inverting the order",
+                })
+            }
+            ("help", "") => message(HELP),
+            ("quit" | "q" | "exit", "") => Ok(Step::Quit),
+            _ => bail!("unknown command {line:?}, see :help"),
+        }
+    }
+
+    /// the longest Lyndon subsequence of `text` with its character positions, and the Lyndon factorization of `text`
+    fn evaluate(&self, text: &str) -> Result<String> {
+        let order = self.order();
+        order.check(text.chars())?;
+        let chars: Vec<char> = text.chars().collect();
+        let keys: Vec<usize> = chars.iter().map(|&c| order.key(c)).collect();
+        let result = longest_lyndon_subsequence(&keys);
+        let subsequence: String = result.positions().iter().map(|&i| chars[i]).collect();
+        let positions: Vec<String> = result.positions().iter().map(usize::to_string).collect();
+        let factors: Vec<String> = lyndon_factorization(&keys)
+            .into_iter()
+            .map(|range| chars[range].iter().collect())
+            .collect();
+        let mut output = String::new();
+        writeln!(output, "subsequence    {subsequence}")?;
+        writeln!(output, "length         {}", result.len())?;
+        writeln!(output, "positions      {}", positions.join(" "))?;
+        write!(output, "factorization  {}", factors.join(" | "))?;
+        Ok(output)
+    }
+}
+
+/// where the input lines come from, with line editing and a history if built with the feature `repl`
+enum Lines {
+    #[cfg(feature = "repl")]
+    Editor(Box<rustyline::Editor<()>>),
+    #[cfg_attr(feature = "repl", allow(dead_code))]
+    Stdin,
+}
+
+impl Lines {
+    fn new() -> Self {
+        #[cfg(feature = "repl")]
+        if let Ok(mut editor) = rustyline::Editor::<()>::new() {
+            if let Some(path) = history_path() {
+                // there is no history the first time
+                let _ = editor.load_history(&path);
+            }
+            return Lines::Editor(Box::new(editor));
+        }
+        Lines::Stdin
+    }
+
+    /// the next line, or `None` at the end of the input
+    fn next(&mut self) -> Result<Option<String>> {
+        match self {
+            #[cfg(feature = "repl")]
+            Lines::Editor(editor) => match editor.readline("> ") {
+                Ok(line) => {
+                    editor.add_history_entry(line.as_str());
+                    Ok(Some(line))
+                }
+                Err(rustyline::error::ReadlineError::Interrupted) => Ok(Some(String::new())),
+                Err(rustyline::error::ReadlineError::Eof) => Ok(None),
+                Err(error) => Err(error.into()),
+            },
+            Lines::Stdin => {
+                print!("> ");
+                io::stdout().flush()?;
+                let mut line = String::new();
+                if io::stdin().lock().read_line(&mut line)? == 0 {
+                    return Ok(None);
+                }
+                Ok(Some(line.trim_end_matches(['\n', '\r']).to_owned()))
+            }
+        }
+    }
+}
+
+impl Drop for Lines {
+    fn drop(&mut self) {
+        #[cfg(feature = "repl")]
+        if let (Lines::Editor(editor), Some(path)) = (self, history_path()) {
+            if let Some(dir) = path.parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+            let _ = editor.save_history(&path);
+        }
+    }
+}
+
+#[cfg(feature = "repl")]
+fn history_path() -> Option<std::path::PathBuf> {
+    crate::config::config_dir().map(|dir| dir.join("history"))
+}
+
+/// reads texts and commands until the end of the input
+pub fn run(mut session: Session) -> Result<()> {
+    let mut lines = Lines::new();
+    println!("{}", HELP.lines().next().unwrap_or_default());
+    while let Some(line) = lines.next()? {
+        if line.is_empty() {
+            continue;
+        }
+        match session.handle(&line) {
+            Ok(Step::Print(output)) => println!("{output}"),
+            Ok(Step::Quit) => break,
+            Err(error) => println!("error: {error:#}"),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_session() {
+    let mut session = Session {
+        ignore_case: false,
+        alphabet: None,
+        unlisted: Unlisted::Error,
+        reverse: false,
+    };
+    let output = |session: &mut Session, line| match session.handle(line).unwrap() {
+        Step::Print(output) => output,
+        Step::Quit => panic!("quit on {line:?}"),
+    };
+    assert_eq!(
+        output(&mut session, "bcab"),
+        "subsequence    ab\nlength         2\npositions      2 3\nfactorization  bc | ab"
+    );
+    output(&mut session, ":alphabet cba");
+    assert!(output(&mut session, "bcab").starts_with("subsequence    cab\n"));
+    assert!(session.handle("d").is_err());
+    output(&mut session, ":unlisted last");
+    assert!(session.handle("d").is_ok());
+    output(&mut session, ":alphabet");
+    output(&mut session, ":reverse");
+    assert!(output(&mut session, "bcab").starts_with("subsequence    cab\n"));
+    assert!(session.handle(":unknown").is_err());
+    assert_eq!(session.handle(":quit").unwrap(), Step::Quit);
+}
@@ -0,0 +1,335 @@
+use std::io::{BufRead, Read, Seek, SeekFrom};
+
+use anyhow::{bail, Result};
+
+/// a named sequence of a FASTA, FASTQ, or 2bit file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequenceRecord {
+    /// the first word of the header line
+    pub id: String,
+    pub sequence: Vec<u8>,
+}
+
+/// the identifier in a header line without its marker, i.e., everything up to the first whitespace
+fn identifier(header: &[u8]) -> String {
+    let header = String::from_utf8_lossy(header);
+    header.split_whitespace().next().unwrap_or("").to_owned()
+}
+
+/// reads a line without its line break into `line`, returning `false` at the end of the input
+fn read_line(reader: &mut impl BufRead, line: &mut Vec<u8>) -> Result<bool> {
+    line.clear();
+    if reader.read_until(b'\n', line)? == 0 {
+        return Ok(false);
+    }
+    while matches!(line.last(), Some(b'\n' | b'\r')) {
+        line.pop();
+    }
+    Ok(true)
+}
+
+/// streams the records of a FASTA file, skipping `;` comment lines and whitespace in the sequences
+pub struct Fasta<R> {
+    reader: R,
+    /// the identifier of the next record, whose header line was already read
+    next_id: Option<String>,
+    line: Vec<u8>,
+}
+
+impl<R: BufRead> Fasta<R> {
+    pub fn new(reader: R) -> Self {
+        Fasta {
+            reader,
+            next_id: None,
+            line: Vec::new(),
+        }
+    }
+
+    /// reads up to the first header line
+    fn first_id(&mut self) -> Result<Option<String>> {
+        while read_line(&mut self.reader, &mut self.line)? {
+            match self.line.first() {
+                Some(b'>') => return Ok(Some(identifier(&self.line[1..]))),
+                Some(b';') | None => {}
+                Some(_) if self.line.iter().all(u8::is_ascii_whitespace) => {}
+                Some(_) => bail!("expected a FASTA header line starting with `>`"),
+            }
+        }
+        Ok(None)
+    }
+
+    fn next_record(&mut self) -> Result<Option<SequenceRecord>> {
+        let id = match self.next_id.take() {
+            Some(id) => id,
+            None => match self.first_id()? {
+                Some(id) => id,
+                None => return Ok(None),
+            },
+        };
+        let mut sequence = Vec::new();
+        while read_line(&mut self.reader, &mut self.line)? {
+            match self.line.first() {
+                Some(b'>') => {
+                    self.next_id = Some(identifier(&self.line[1..]));
+                    break;
+                }
+                Some(b';') => {}
+                _ => sequence.extend(self.line.iter().filter(|c| !c.is_ascii_whitespace())),
+            }
+        }
+        Ok(Some(SequenceRecord { id, sequence }))
+    }
+}
+
+impl<R: BufRead> Iterator for Fasta<R> {
+    type Item = Result<SequenceRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_record().transpose()
+    }
+}
+
+/// streams the reads of a FASTQ file, each consisting of the four lines header, sequence, `+`, and quality
+pub struct Fastq<R> {
+    reader: R,
+    line: Vec<u8>,
+}
+
+impl<R: BufRead> Fastq<R> {
+    pub fn new(reader: R) -> Self {
+        Fastq {
+            reader,
+            line: Vec::new(),
+        }
+    }
+
+    fn next_record(&mut self) -> Result<Option<SequenceRecord>> {
+        // reads may be separated by empty lines
+        loop {
+            if !read_line(&mut self.reader, &mut self.line)? {
+                return Ok(None);
+            }
+            if !self.line.is_empty() {
+                break;
+            }
+        }
+        if self.line[0] != b'@' {
+            bail!("expected a FASTQ header line starting with `@`");
+        }
+        let id = identifier(&self.line[1..]);
+        let mut sequence = Vec::new();
+        if !read_line(&mut self.reader, &mut sequence)? {
+            bail!("the read {id} has no sequence");
+        }
+        if !read_line(&mut self.reader, &mut self.line)? || self.line.first() != Some(&b'+') {
+            bail!("expected a `+` line after the sequence of the read {id}");
+        }
+        if !read_line(&mut self.reader, &mut self.line)? || self.line.len() != sequence.len() {
+            bail!("the quality of the read {id} does not match the length of its sequence");
+        }
+        Ok(Some(SequenceRecord { id, sequence }))
+    }
+}
+
+impl<R: BufRead> Iterator for Fastq<R> {
+    type Item = Result<SequenceRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_record().transpose()
+    }
+}
+
+/// the first four bytes of a 2bit file, in the byte order of the file
+const TWO_BIT_SIGNATURE: u32 = 0x1a41_2743;
+
+/// the nucleotides of the two-bit codes
+const TWO_BIT_NUCLEOTIDES: &[u8; 4] = b"TCAG";
+
+/// streams the sequences of a UCSC 2bit file, unpacked with unknown nucleotides as `N` and masked ones in lowercase
+pub struct TwoBit<R> {
+    reader: R,
+    big_endian: bool,
+    /// the names of the sequences not read yet with their positions in the file
+    index: std::vec::IntoIter<(String, u64)>,
+}
+
+impl<R: Read + Seek> TwoBit<R> {
+    /// reads the header and the index of the sequences
+    pub fn new(reader: R) -> Result<Self> {
+        let mut two_bit = TwoBit {
+            reader,
+            big_endian: false,
+            index: Vec::new().into_iter(),
+        };
+        let mut signature = [0; 4];
+        two_bit.reader.read_exact(&mut signature)?;
+        two_bit.big_endian = match signature {
+            _ if u32::from_le_bytes(signature) == TWO_BIT_SIGNATURE => false,
+            _ if u32::from_be_bytes(signature) == TWO_BIT_SIGNATURE => true,
+            _ => bail!("not a 2bit file"),
+        };
+        let version = two_bit.read_u32()?;
+        if version > 1 {
+            bail!("unsupported 2bit version {version}");
+        }
+        let count = two_bit.read_u32()?;
+        two_bit.read_u32()?; // reserved
+        let mut index = Vec::new();
+        for _ in 0..count {
+            let mut name = vec![0; two_bit.read_bytes::<1>()?[0].into()];
+            two_bit.reader.read_exact(&mut name)?;
+            // version 1 has 64-bit offsets for files larger than 4 GiB
+            let offset = match version {
+                0 => two_bit.read_u32()?.into(),
+                _ => two_bit.read_u64()?,
+            };
+            index.push((String::from_utf8_lossy(&name).into_owned(), offset));
+        }
+        two_bit.index = index.into_iter();
+        Ok(two_bit)
+    }
+
+    fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut bytes = [0; N];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        let bytes = self.read_bytes()?;
+        Ok(match self.big_endian {
+            true => u32::from_be_bytes(bytes),
+            false => u32::from_le_bytes(bytes),
+        })
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        let bytes = self.read_bytes()?;
+        Ok(match self.big_endian {
+            true => u64::from_be_bytes(bytes),
+            false => u64::from_le_bytes(bytes),
+        })
+    }
+
+    /// reads the starting positions and the lengths of blocks, e.g., of unknown nucleotides
+    fn read_blocks(&mut self) -> Result<Vec<(usize, usize)>> {
+        let count = self.read_u32()?;
+        let starts = (0..count)
+            .map(|_| self.read_u32())
+            .collect::<Result<Vec<u32>>>()?;
+        starts
+            .into_iter()
+            .map(|start| Ok((start as usize, self.read_u32()? as usize)))
+            .collect()
+    }
+
+    /// reads and unpacks the sequence stored at `offset`
+    fn read_sequence(&mut self, offset: u64) -> Result<Vec<u8>> {
+        self.reader.seek(SeekFrom::Start(offset))?;
+        let len = self.read_u32()? as usize;
+        let unknown = self.read_blocks()?;
+        let masked = self.read_blocks()?;
+        self.read_u32()?; // reserved
+        let mut packed = vec![0; len.div_ceil(4)];
+        self.reader.read_exact(&mut packed)?;
+        let mut sequence: Vec<u8> = packed
+            .iter()
+            .flat_map(|&byte| (0..4).map(move |i| (byte >> (6 - 2 * i)) & 3))
+            .take(len)
+            .map(|code| TWO_BIT_NUCLEOTIDES[usize::from(code)])
+            .collect();
+        for (blocks, unpack) in [
+            (unknown, (|block| block.fill(b'N')) as fn(&mut [u8])),
+            (masked, <[u8]>::make_ascii_lowercase),
+        ] {
+            for (start, length) in blocks {
+                match sequence.get_mut(start..start.saturating_add(length)) {
+                    Some(block) => unpack(block),
+                    None => bail!("a block exceeds the sequence of length {len}"),
+                }
+            }
+        }
+        Ok(sequence)
+    }
+}
+
+impl<R: Read + Seek> Iterator for TwoBit<R> {
+    type Item = Result<SequenceRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (id, offset) = self.index.next()?;
+        Some(
+            self.read_sequence(offset)
+                .map(|sequence| SequenceRecord { id, sequence }),
+        )
+    }
+}
+
+#[test]
+fn test_fasta() {
+    let input = b"; comment\n>seq1 first sequence\nACGT\r\nAC GT\n\n>seq2\n>seq3\nTTA\n";
+    let records: Vec<SequenceRecord> = Fasta::new(&input[..]).map(Result::unwrap).collect();
+    let expected = [("seq1", &b"ACGTACGT"[..]), ("seq2", b""), ("seq3", b"TTA")];
+    assert_eq!(records.len(), expected.len());
+    for (record, (id, sequence)) in records.iter().zip(expected) {
+        assert_eq!(record.id, id);
+        assert_eq!(record.sequence, sequence);
+    }
+    assert!(Fasta::new(&b"ACGT\n>seq1\n"[..]).next().unwrap().is_err());
+    assert!(Fasta::new(&b""[..]).next().is_none());
+}
+
+#[test]
+fn test_fastq() {
+    let input = b"@read1 run=1\nACGT\n+\nIIII\n\n@read2\r\nTTA\r\n+read2\r\n#I#\r\n";
+    let records: Vec<SequenceRecord> = Fastq::new(&input[..]).map(Result::unwrap).collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(
+        (records[0].id.as_str(), &records[0].sequence[..]),
+        ("read1", &b"ACGT"[..])
+    );
+    assert_eq!(
+        (records[1].id.as_str(), &records[1].sequence[..]),
+        ("read2", &b"TTA"[..])
+    );
+    assert!(Fastq::new(&b"@read1\nACGT\n+\nIII\n"[..])
+        .next()
+        .unwrap()
+        .is_err());
+    assert!(Fastq::new(&b">read1\nACGT\n"[..]).next().unwrap().is_err());
+}
+
+#[test]
+fn test_two_bit() {
+    // chr1 = ACGTNNacgT (N at 4..6, lowercase at 6..9), chr2 = GGA
+    let mut file = Vec::new();
+    for word in [TWO_BIT_SIGNATURE, 0, 2, 0] {
+        file.extend(word.to_le_bytes());
+    }
+    let header_len = file.len() + 2 * (1 + 4 + 4);
+    let chr1 = [10, 1, 4, 2, 1, 6, 3, 0].map(u32::to_le_bytes).concat();
+    let chr1 = [chr1, vec![0b1001_1100, 0b0000_1001, 0b1100_0000]].concat();
+    for (name, offset) in [("chr1", header_len), ("chr2", header_len + chr1.len())] {
+        file.push(name.len() as u8);
+        file.extend(name.as_bytes());
+        file.extend((offset as u32).to_le_bytes());
+    }
+    file.extend(chr1);
+    file.extend([3, 0, 0, 0].map(u32::to_le_bytes).concat());
+    file.push(0b1111_1000);
+
+    let records: Vec<SequenceRecord> = TwoBit::new(std::io::Cursor::new(file))
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(
+        (records[0].id.as_str(), &records[0].sequence[..]),
+        ("chr1", &b"ACGTNNacgT"[..])
+    );
+    assert_eq!(
+        (records[1].id.as_str(), &records[1].sequence[..]),
+        ("chr2", &b"GGA"[..])
+    );
+    assert!(TwoBit::new(std::io::Cursor::new(b">chr1\nACGT\n".to_vec())).is_err());
+}
@@ -0,0 +1,85 @@
+use std::io::Read;
+use std::time::Instant;
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::json;
+use tiny_http::{Header, Method, Response, Server};
+
+use crate::output::to_json;
+use crate::Solution;
+
+/// the bytes of `body`, or `None` if there are more than `limit`
+fn read_body(body: impl Read, limit: u64) -> Result<Option<Vec<u8>>> {
+    let mut text = Vec::new();
+    body.take(limit.saturating_add(1))
+        .read_to_end(&mut text)
+        .context("cannot read the request")?;
+    Ok((text.len() as u64 <= limit).then_some(text))
+}
+
+/// answers `POST /lls` with the longest Lyndon subsequence of the request body computed by `solve`, until the process ends, telling where unless `quiet`
+///
+/// Bodies longer than `max_body` bytes are not read, but answered with 413 Payload Too Large.
+pub fn run(
+    address: &str,
+    port: u16,
+    max_body: u64,
+    quiet: bool,
+    mut solve: impl FnMut(Vec<u8>) -> Result<Solution>,
+) -> Result<()> {
+    let server = Server::http((address, port))
+        .map_err(|error| anyhow!(error))
+        .with_context(|| format!("cannot listen on {address}:{port}"))?;
+    if !quiet {
+        eprintln!("listening on http://{address}:{port}/lls");
+    }
+    for mut request in server.incoming_requests() {
+        let (status, body) = match (request.method(), request.url()) {
+            (Method::Post, "/lls") => {
+                let result = read_body(request.as_reader(), max_body).and_then(|text| {
+                    let Some(text) = text else {
+                        return Ok(None);
+                    };
+                    let started = Instant::now();
+                    let solution = solve(text)?;
+                    Ok(Some(to_json(
+                        "request",
+                        &solution,
+                        started.elapsed(),
+                        false,
+                    )))
+                });
+                match result {
+                    Ok(Some(value)) => (200, value),
+                    Ok(None) => (
+                        413,
+                        json!({ "error": format!("the text is longer than {max_body} bytes, see --max-body") }),
+                    ),
+                    Err(error) => (400, json!({ "error": format!("{error:#}") })),
+                }
+            }
+            (_, "/lls") => (405, json!({ "error": "send the text with POST" })),
+            (_, url) => (
+                404,
+                json!({ "error": format!("{url} not found, use /lls") }),
+            ),
+        };
+        let header = Header::from_bytes("Content-Type", "application/json").expect("valid header");
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(header);
+        // a client hanging up does not concern the others
+        if let Err(error) = request.respond(response) {
+            eprintln!("Error: cannot respond: {error}");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_read_body() {
+    let body = b"\xff\x00\xfe\x01";
+    assert_eq!(read_body(&body[..], 4).unwrap().as_deref(), Some(&body[..]));
+    assert!(read_body(&body[..], 3).unwrap().is_none());
+    assert!(read_body(&body[..], 0).unwrap().is_none());
+}
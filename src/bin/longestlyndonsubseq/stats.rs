@@ -0,0 +1,367 @@
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, Write};
+use std::time::Instant;
+
+use anyhow::Result;
+use longestlyndonsubseq::{longest_lyndon_subsequence_with_events, SearchEvent};
+use serde_json::{json, Value};
+
+/// the summary of a run written by `--stats`
+pub struct Stats {
+    started: Instant,
+    texts: usize,
+    input_bytes: usize,
+    /// the keys of the symbols in the texts, or `None` once a text has symbols without keys, i.e., graphemes or tokens
+    alphabet: Option<HashSet<usize>>,
+    /// the length of the longest result
+    result_length: Option<usize>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Stats {
+            started: Instant::now(),
+            texts: 0,
+            input_bytes: 0,
+            alphabet: Some(HashSet::new()),
+            result_length: None,
+        }
+    }
+
+    /// counts a text of `len` bytes whose symbols compare by `keys`
+    pub fn add_text(&mut self, len: usize, keys: Option<&[usize]>) {
+        self.texts += 1;
+        self.input_bytes += len;
+        match (&mut self.alphabet, keys) {
+            (Some(alphabet), Some(keys)) => alphabet.extend(keys),
+            _ => self.alphabet = None,
+        }
+    }
+
+    pub fn add_result(&mut self, len: usize) {
+        self.result_length = self.result_length.max(Some(len));
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "elapsed_ms": self.started.elapsed().as_secs_f64() * 1000.0,
+            "peak_memory_bytes": peak_memory(),
+            "input_bytes": self.input_bytes,
+            "texts": self.texts,
+            "alphabet_size": self.alphabet.as_ref().map(HashSet::len),
+            "result_length": self.result_length,
+        })
+    }
+
+    /// writes the summary to the standard error, as a JSON object if `json`
+    pub fn write(&self, json: bool) -> Result<()> {
+        let stats = self.to_json();
+        let mut err = io::stderr().lock();
+        if json {
+            serde_json::to_writer(&mut err, &stats)?;
+            writeln!(err)?;
+            return Ok(());
+        }
+        let unknown = || "unknown".to_owned();
+        let number = |key: &str| stats[key].as_u64().map_or_else(unknown, |n| n.to_string());
+        writeln!(
+            err,
+            "wall-clock time  {:.3} ms",
+            stats["elapsed_ms"].as_f64().unwrap_or_default()
+        )?;
+        writeln!(
+            err,
+            "peak memory      {}",
+            stats["peak_memory_bytes"]
+                .as_u64()
+                .map_or_else(unknown, |bytes| format!(
+                    "{:.1} MiB",
+                    bytes as f64 / (1 << 20) as f64
+                ))
+        )?;
+        writeln!(err, "input size       {} bytes", self.input_bytes)?;
+        writeln!(err, "texts            {}", self.texts)?;
+        writeln!(err, "alphabet size    {}", number("alphabet_size"))?;
+        writeln!(err, "result length    {}", number("result_length"))?;
+        Ok(())
+    }
+}
+
+/// the aggregate of the results of many texts written by `--summary`
+pub struct Summary {
+    input_bytes: usize,
+    /// the length of the result of each text
+    lengths: Vec<usize>,
+    /// the label and result length of the first text with the longest result
+    best: Option<(String, usize)>,
+}
+
+/// the width of the longest bar of the histogram
+const HISTOGRAM_WIDTH: usize = 40;
+
+impl Summary {
+    pub fn new() -> Self {
+        Summary {
+            input_bytes: 0,
+            lengths: Vec::new(),
+            best: None,
+        }
+    }
+
+    /// counts the text with `label` of `len` bytes whose result has `length` characters
+    pub fn add(&mut self, label: &str, len: usize, length: usize) {
+        self.input_bytes += len;
+        self.lengths.push(length);
+        if self.best.as_ref().is_none_or(|best| best.1 < length) {
+            self.best = Some((label.to_owned(), length));
+        }
+    }
+
+    /// the number of texts with each result length
+    fn histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        for &length in &self.lengths {
+            *histogram.entry(length).or_default() += 1;
+        }
+        histogram
+    }
+
+    fn median(&self) -> Option<f64> {
+        let mut lengths = self.lengths.clone();
+        lengths.sort_unstable();
+        let middle = lengths.len() / 2;
+        match lengths.len() {
+            0 => None,
+            len if len % 2 == 1 => Some(lengths[middle] as f64),
+            _ => Some((lengths[middle - 1] + lengths[middle]) as f64 / 2.0),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let mean = match self.lengths.len() {
+            0 => None,
+            texts => Some(self.lengths.iter().sum::<usize>() as f64 / texts as f64),
+        };
+        let histogram: serde_json::Map<String, Value> = self
+            .histogram()
+            .into_iter()
+            .map(|(length, count)| (length.to_string(), count.into()))
+            .collect();
+        json!({
+            "texts": self.lengths.len(),
+            "input_bytes": self.input_bytes,
+            "mean_length": mean,
+            "median_length": self.median(),
+            "best": self.best.as_ref().map(|(label, length)| json!({"input": label, "length": length})),
+            "length_histogram": histogram,
+        })
+    }
+
+    /// writes the summary to the standard error, as a JSON object if `json`
+    pub fn write(&self, json: bool) -> Result<()> {
+        let summary = self.to_json();
+        let mut err = io::stderr().lock();
+        if json {
+            serde_json::to_writer(&mut err, &summary)?;
+            writeln!(err)?;
+            return Ok(());
+        }
+        let number = |key: &str| {
+            summary[key]
+                .as_f64()
+                .map_or_else(|| "unknown".to_owned(), |n| format!("{n:.2}"))
+        };
+        writeln!(err, "texts            {}", self.lengths.len())?;
+        writeln!(err, "input size       {} bytes", self.input_bytes)?;
+        writeln!(err, "mean length      {}", number("mean_length"))?;
+        writeln!(err, "median length    {}", number("median_length"))?;
+        if let Some((label, length)) = &self.best {
+            writeln!(err, "best             {label} (length {length})")?;
+        }
+        let histogram = self.histogram();
+        let rows = histogram
+            .iter()
+            .map(|(length, &count)| (length.to_string(), count));
+        write_histogram(&mut err, rows.collect())
+    }
+}
+
+/// writes a bar for the count of each label of `rows`, scaled to the largest count
+fn write_histogram(mut err: impl Write, rows: Vec<(String, usize)>) -> Result<()> {
+    let width = rows
+        .iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or_default();
+    let most = rows
+        .iter()
+        .map(|&(_, count)| count)
+        .max()
+        .unwrap_or_default();
+    for (label, count) in rows {
+        // every label that occurs gets at least a short bar
+        let bar = (count * HISTOGRAM_WIDTH).div_ceil(most);
+        writeln!(
+            err,
+            "  {label:>width$} {:<HISTOGRAM_WIDTH$} {count}",
+            "#".repeat(bar)
+        )?;
+    }
+    Ok(())
+}
+
+/// histograms of the internals of the searches written by `--stats-detail`
+pub struct SearchDetail {
+    /// the number of pushed characters by the length of the subsequence they end
+    depths: BTreeMap<usize, usize>,
+    /// the number of subsequences by the number of their extensions that the search visits
+    branching: BTreeMap<usize, usize>,
+    /// the number of successor queries by the bucket of the number of positions a scan would read, see `bucket`
+    scans: BTreeMap<usize, usize>,
+}
+
+/// the bucket of `len`: 0 for 0, and `b` for `2^(b-1) <= len < 2^b`
+fn bucket(len: usize) -> usize {
+    (usize::BITS - len.leading_zeros()) as usize
+}
+
+/// the range of lengths in a `bucket`
+fn bucket_label(bucket: usize) -> String {
+    match bucket {
+        0 | 1 => bucket.to_string(),
+        _ => format!("{}-{}", 1usize << (bucket - 1), (1usize << bucket) - 1),
+    }
+}
+
+impl SearchDetail {
+    pub fn new() -> Self {
+        SearchDetail {
+            depths: BTreeMap::new(),
+            branching: BTreeMap::new(),
+            scans: BTreeMap::new(),
+        }
+    }
+
+    /// searches the `keys` again, counting the steps of the search
+    pub fn add(&mut self, keys: &[usize]) {
+        // the number of visited extensions of each subsequence on the stack
+        let mut children: Vec<usize> = Vec::new();
+        longest_lyndon_subsequence_with_events(keys, |event| match event {
+            SearchEvent::Push { depth, .. } => {
+                *self.depths.entry(depth).or_default() += 1;
+                if let Some(siblings) = children.last_mut() {
+                    *siblings += 1;
+                }
+                children.push(0);
+            }
+            SearchEvent::Pop { .. } => {
+                let count = children.pop().unwrap_or_default();
+                *self.branching.entry(count).or_default() += 1;
+            }
+            SearchEvent::Successor { start, found, .. } => {
+                // up to the found position, or to the end of the text
+                let scanned = found.map_or(keys.len(), |found| found + 1) - start;
+                *self.scans.entry(bucket(scanned)).or_default() += 1;
+            }
+            _ => {}
+        });
+    }
+
+    fn to_json(&self) -> Value {
+        let histogram = |histogram: &BTreeMap<usize, usize>, label: fn(usize) -> String| {
+            let histogram: serde_json::Map<String, Value> = histogram
+                .iter()
+                .map(|(&key, &count)| (label(key), count.into()))
+                .collect();
+            histogram
+        };
+        json!({
+            "stack_depth": histogram(&self.depths, |depth| depth.to_string()),
+            "branching_factor": histogram(&self.branching, |count| count.to_string()),
+            "successor_scan_length": histogram(&self.scans, bucket_label),
+        })
+    }
+
+    /// writes the histograms to the standard error, as a JSON object if `json`
+    pub fn write(&self, json: bool) -> Result<()> {
+        let mut err = io::stderr().lock();
+        if json {
+            serde_json::to_writer(&mut err, &self.to_json())?;
+            writeln!(err)?;
+            return Ok(());
+        }
+        let histograms = [
+            ("stack depth", &self.depths, false),
+            ("branching factor", &self.branching, false),
+            ("successor scan length", &self.scans, true),
+        ];
+        for (title, histogram, bucketed) in histograms {
+            writeln!(err, "{title}")?;
+            let rows = histogram.iter().map(|(&key, &count)| match bucketed {
+                true => (bucket_label(key), count),
+                false => (key.to_string(), count),
+            });
+            write_histogram(&mut err, rows.collect())?;
+        }
+        Ok(())
+    }
+}
+
+/// the largest resident set size of the process so far, only known on Linux
+fn peak_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?;
+    let kib: u64 = line.trim().strip_suffix("kB")?.trim().parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[test]
+fn test_stats() {
+    let mut stats = Stats::new();
+    stats.add_text(4, Some(&[1, 2, 0, 1]));
+    stats.add_result(2);
+    stats.add_text(3, Some(&[3, 3, 3]));
+    stats.add_result(1);
+    let json = stats.to_json();
+    assert_eq!(json["input_bytes"], 7);
+    assert_eq!(json["texts"], 2);
+    assert_eq!(json["alphabet_size"], 4);
+    assert_eq!(json["result_length"], 2);
+    stats.add_text(1, None);
+    assert!(stats.to_json()["alphabet_size"].is_null());
+    #[cfg(target_os = "linux")]
+    assert!(peak_memory().unwrap() > 0);
+}
+
+#[test]
+fn test_search_detail() {
+    assert_eq!(
+        [0, 1, 2, 3, 4, 7, 8].map(bucket).map(bucket_label),
+        ["0", "1", "2-3", "2-3", "4-7", "4-7", "8-15"]
+    );
+    let mut detail = SearchDetail::new();
+    // a, aa, aab, ab and b
+    detail.add(&[0, 0, 1]);
+    let json = detail.to_json();
+    assert_eq!(json["stack_depth"], json!({"1": 2, "2": 2, "3": 1}));
+    assert_eq!(json["branching_factor"], json!({"0": 3, "1": 1, "2": 1}));
+    assert!(json["successor_scan_length"]["1"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn test_summary() {
+    let mut summary = Summary::new();
+    assert!(summary.to_json()["median_length"].is_null());
+    for (label, length) in [("a", 3), ("b", 5), ("c", 5), ("d", 2)] {
+        summary.add(label, 10, length);
+    }
+    let json = summary.to_json();
+    assert_eq!(json["texts"], 4);
+    assert_eq!(json["input_bytes"], 40);
+    assert_eq!(json["mean_length"], 3.75);
+    assert_eq!(json["median_length"], 4.0);
+    assert_eq!(json["best"], json!({"input": "b", "length": 5}));
+    assert_eq!(json["length_histogram"], json!({"2": 1, "3": 1, "5": 2}));
+}
@@ -0,0 +1,485 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+use anyhow::{bail, Context, Result};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+use longestlyndonsubseq::longest_lyndon_subsequence;
+
+/// how grapheme clusters are compared
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Collation {
+    /// by their codepoints
+    Codepoint,
+    /// by their codepoints with the case folded by [`fold_case`]
+    Caseless,
+    /// by their case-folded base characters, ignoring combining marks, e.g., `É` equals `e`
+    Base,
+}
+
+impl Collation {
+    /// the string by which `cluster` is compared
+    fn key(self, cluster: &str) -> String {
+        match self {
+            Collation::Codepoint => cluster.to_owned(),
+            Collation::Caseless => cluster.chars().map(fold_case).collect(),
+            Collation::Base => cluster
+                .nfd()
+                .filter(|&c| !is_combining_mark(c))
+                .map(fold_case)
+                .collect(),
+        }
+    }
+}
+
+/// computes a longest Lyndon subsequence of the extended grapheme clusters of `text`, returning it with its number of clusters
+pub fn longest_lyndon_subsequence_graphemes(text: &str, collation: Collation) -> (String, usize) {
+    let clusters: Vec<&str> = text.graphemes(true).collect();
+    let keys: Vec<String> = clusters
+        .iter()
+        .map(|cluster| collation.key(cluster))
+        .collect();
+    let result = longest_lyndon_subsequence(&ranks(&keys));
+    let subsequence = result.positions().iter().map(|&i| clusters[i]).collect();
+    (subsequence, result.len())
+}
+
+/// computes a longest Lyndon subsequence of the words of `text` separated by ASCII whitespace, with words compared lexicographically
+/// (regardless of the case of ASCII letters if `ignore_case`)
+///
+/// Returns the words of the subsequence separated by spaces, and the number of its words.
+pub fn longest_lyndon_subsequence_tokens(text: &[u8], ignore_case: bool) -> (Vec<u8>, usize) {
+    let words: Vec<&[u8]> = text
+        .split(u8::is_ascii_whitespace)
+        .filter(|word| !word.is_empty())
+        .collect();
+    let ranks = match ignore_case {
+        true => ranks(
+            &words
+                .iter()
+                .map(|word| word.to_ascii_lowercase())
+                .collect::<Vec<_>>(),
+        ),
+        false => ranks(&words),
+    };
+    let result = longest_lyndon_subsequence(&ranks);
+    let subsequence: Vec<&[u8]> = result.positions().iter().map(|&i| words[i]).collect();
+    (subsequence.join(&b' '), result.len())
+}
+
+/// the simple case folding of `c`, the mappings with status C and S of Unicode's `CaseFolding.txt`
+///
+/// It is the lowercase form of `c` if that is a single character, except for the characters listed here,
+/// e.g., the long s `ſ` folds to `s` and the final sigma `ς` to `σ`, and Cherokee folds to uppercase.
+pub fn fold_case(c: char) -> char {
+    match c {
+        '\u{b5}' => '\u{3bc}',
+        '\u{17f}' => 's',
+        '\u{345}' | '\u{1fbe}' => '\u{3b9}',
+        '\u{3c2}' => '\u{3c3}',
+        '\u{3d0}' => '\u{3b2}',
+        '\u{3d1}' => '\u{3b8}',
+        '\u{3d5}' => '\u{3c6}',
+        '\u{3d6}' => '\u{3c0}',
+        '\u{3f0}' => '\u{3ba}',
+        '\u{3f1}' => '\u{3c1}',
+        '\u{3f5}' => '\u{3b5}',
+        '\u{1c80}' => '\u{432}',
+        '\u{1c81}' => '\u{434}',
+        '\u{1c82}' => '\u{43e}',
+        '\u{1c83}' => '\u{441}',
+        '\u{1c84}' | '\u{1c85}' => '\u{442}',
+        '\u{1c86}' => '\u{44a}',
+        '\u{1c87}' => '\u{463}',
+        '\u{1c88}' => '\u{a64b}',
+        '\u{1e9b}' => '\u{1e61}',
+        '\u{1fd3}' => '\u{390}',
+        '\u{1fe3}' => '\u{3b0}',
+        '\u{fb05}' => '\u{fb06}',
+        // Cherokee folds to its uppercase letters, which are older
+        '\u{13a0}'..='\u{13f5}' => c,
+        '\u{13f8}'..='\u{13fd}' => char::from_u32(c as u32 - 8).unwrap_or(c),
+        '\u{ab70}'..='\u{abbf}' => char::from_u32(c as u32 - 0xab70 + 0x13a0).unwrap_or(c),
+        _ => {
+            let mut lowercase = c.to_lowercase();
+            match (lowercase.next(), lowercase.next()) {
+                (Some(folded), None) => folded,
+                _ => c,
+            }
+        }
+    }
+}
+
+/// a set of characters like `a-z0-9_`, see [`parse_char_set`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharSet {
+    ranges: Vec<RangeInclusive<char>>,
+}
+
+impl CharSet {
+    pub fn contains(&self, c: char) -> bool {
+        self.ranges.iter().any(|range| range.contains(&c))
+    }
+}
+
+/// parses a set of characters and ranges like `a-z`, with the escapes `\n`, `\r`, `\t`, `\0`, `\\`, `\-`, and `\xNN`
+pub fn parse_char_set(spec: &str) -> Result<CharSet> {
+    // the characters with whether they were escaped
+    let mut symbols = Vec::new();
+    let mut chars = spec.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            symbols.push((c, false));
+            continue;
+        }
+        let escaped = match chars.next() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('0') => '\0',
+            Some('x') => {
+                let digits: String = chars.by_ref().take(2).collect();
+                let byte = u8::from_str_radix(&digits, 16).with_context(|| {
+                    format!("expected two hexadecimal digits after \\x in {spec:?}")
+                })?;
+                char::from(byte)
+            }
+            Some(c @ ('\\' | '-')) => c,
+            c => bail!(
+                "unknown escape \\{} in {spec:?}",
+                c.map_or(String::new(), String::from)
+            ),
+        };
+        symbols.push((escaped, true));
+    }
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < symbols.len() {
+        match symbols.get(i + 1..i + 3) {
+            Some([('-', false), (last, _)]) => {
+                let first = symbols[i].0;
+                if first > *last {
+                    bail!("the range {first}-{last} in {spec:?} is empty");
+                }
+                ranges.push(first..=*last);
+                i += 3;
+            }
+            _ => {
+                ranges.push(symbols[i].0..=symbols[i].0);
+                i += 1;
+            }
+        }
+    }
+    Ok(CharSet { ranges })
+}
+
+/// restricts the alphabet to the characters in `include`, if given, that are not in `exclude`
+#[derive(Debug, Clone, Default)]
+pub struct CharFilter {
+    pub include: Option<CharSet>,
+    pub exclude: Option<CharSet>,
+    /// also drops ASCII whitespace
+    pub strip_whitespace: bool,
+}
+
+impl CharFilter {
+    fn is_empty(&self) -> bool {
+        self.include.is_none() && self.exclude.is_none() && !self.strip_whitespace
+    }
+
+    fn keeps(&self, c: char) -> bool {
+        self.include.as_ref().is_none_or(|set| set.contains(c))
+            && !self.exclude.as_ref().is_some_and(|set| set.contains(c))
+            && !(self.strip_whitespace && c.is_ascii_whitespace())
+    }
+
+    /// the bytes of `text` to keep, where a byte is the character of the same value, e.g., `\xff` is `ÿ`
+    pub fn bytes<'a>(&self, text: &'a [u8]) -> Cow<'a, [u8]> {
+        if self.is_empty() {
+            return text.into();
+        }
+        text.iter()
+            .copied()
+            .filter(|&byte| self.keeps(char::from(byte)))
+            .collect::<Vec<u8>>()
+            .into()
+    }
+
+    /// the indices of the bytes of `text` kept by [`bytes`](Self::bytes), or `None` if all are kept
+    pub fn kept_bytes(&self, text: &[u8]) -> Option<Vec<usize>> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(
+            (0..text.len())
+                .filter(|&i| self.keeps(char::from(text[i])))
+                .collect(),
+        )
+    }
+
+    /// the indices of the characters of `text` kept by [`chars`](Self::chars), or `None` if all are kept
+    pub fn kept_chars(&self, text: &str) -> Option<Vec<usize>> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(
+            text.chars()
+                .enumerate()
+                .filter(|&(_, c)| self.keeps(c))
+                .map(|(i, _)| i)
+                .collect(),
+        )
+    }
+
+    /// the characters of `text` to keep
+    pub fn chars<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.is_empty() {
+            return text.into();
+        }
+        text.chars()
+            .filter(|&c| self.keeps(c))
+            .collect::<String>()
+            .into()
+    }
+}
+
+/// what happens to characters missing from an explicit alphabet
+#[derive(clap::ArgEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Unlisted {
+    /// the input must not contain them
+    #[default]
+    Error,
+    /// they are larger than the characters of the alphabet, and ordered by their values
+    Last,
+}
+
+/// an explicit order of characters like `ACGT`, see [`parse_alphabet`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    ranks: HashMap<char, usize>,
+}
+
+impl Alphabet {
+    /// the characters of the alphabet in ascending order
+    pub fn chars(&self) -> Vec<char> {
+        let mut chars: Vec<char> = self.ranks.keys().copied().collect();
+        chars.sort_by_key(|c| self.ranks[c]);
+        chars
+    }
+}
+
+/// parses the characters of an alphabet in ascending order, given like a [`CharSet`], i.e., `a-z` lists the lowercase letters
+pub fn parse_alphabet(spec: &str) -> Result<Alphabet> {
+    let mut ranks = HashMap::new();
+    for c in parse_char_set(spec)?.ranges.into_iter().flatten() {
+        let rank = ranks.len();
+        if ranks.insert(c, rank).is_some() {
+            bail!("{c:?} occurs twice in the alphabet {spec:?}");
+        }
+    }
+    Ok(Alphabet { ranks })
+}
+
+/// the order in which the characters are compared
+#[derive(Debug, Clone, Default)]
+pub struct Order {
+    ignore_case: bool,
+    unicode: bool,
+    alphabet: Option<Alphabet>,
+    unlisted: Unlisted,
+    reverse: bool,
+}
+
+impl Order {
+    /// the order of `alphabet`, or of the values of the characters, which is inverted if `reverse`
+    /// - `ignore_case` : compares the letters regardless of their case, i.e., the ASCII letters, or all letters if `unicode`
+    pub fn new(
+        ignore_case: bool,
+        unicode: bool,
+        alphabet: Option<Alphabet>,
+        unlisted: Unlisted,
+        reverse: bool,
+    ) -> Self {
+        let mut order = Order {
+            ignore_case,
+            unicode,
+            alphabet: None,
+            unlisted,
+            reverse,
+        };
+        // the alphabet lists the folded characters, where the first of a character and its other cases counts
+        order.alphabet = alphabet.map(|alphabet| {
+            let mut listed: Vec<(usize, char)> = alphabet
+                .ranks
+                .into_iter()
+                .map(|(c, rank)| (rank, c))
+                .collect();
+            listed.sort_unstable();
+            let mut ranks = HashMap::new();
+            for (_, c) in listed {
+                let rank = ranks.len();
+                ranks.entry(order.fold(c)).or_insert(rank);
+            }
+            Alphabet { ranks }
+        });
+        order
+    }
+
+    /// whether the characters are compared by their values
+    pub fn is_natural(&self) -> bool {
+        !self.ignore_case && self.alphabet.is_none() && !self.reverse
+    }
+
+    /// `c` with its case folded if the case is ignored
+    fn fold(&self, c: char) -> char {
+        match (self.ignore_case, self.unicode) {
+            (false, _) => c,
+            (true, false) => c.to_ascii_lowercase(),
+            (true, true) => fold_case(c),
+        }
+    }
+
+    /// the key by which `c` is compared
+    pub fn key(&self, c: char) -> usize {
+        let c = self.fold(c);
+        let key = match &self.alphabet {
+            None => c as usize,
+            Some(alphabet) => alphabet
+                .ranks
+                .get(&c)
+                .copied()
+                .unwrap_or(alphabet.ranks.len() + c as usize),
+        };
+        match self.reverse {
+            true => usize::MAX - key,
+            false => key,
+        }
+    }
+
+    /// fails for the first of `chars` that is not in the alphabet, unless those are allowed
+    pub fn check(&self, mut chars: impl Iterator<Item = char>) -> Result<()> {
+        let alphabet = match &self.alphabet {
+            Some(alphabet) if self.unlisted == Unlisted::Error => alphabet,
+            _ => return Ok(()),
+        };
+        match chars.find(|&c| !alphabet.ranks.contains_key(&self.fold(c))) {
+            Some(c) => bail!("the character {c:?} is not in the alphabet"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// the rank of each of `keys` among the distinct keys, such that the ranks compare like the keys
+fn ranks<K: Ord>(keys: &[K]) -> Vec<usize> {
+    let mut alphabet: Vec<&K> = keys.iter().collect();
+    alphabet.sort_unstable();
+    alphabet.dedup();
+    keys.iter()
+        .map(|key| alphabet.binary_search(&key).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_graphemes() {
+    let graphemes = longest_lyndon_subsequence_graphemes;
+    // the combining acute accent stays with its base character
+    assert_eq!(
+        graphemes("be\u{301}a", Collation::Codepoint),
+        ("be\u{301}".to_owned(), 2)
+    );
+    assert_eq!(graphemes("E\u{301}\u{1f600}", Collation::Codepoint).1, 2);
+    assert_eq!(graphemes("Ee\u{301}", Collation::Codepoint).1, 2);
+    assert_eq!(graphemes("Ee\u{301}", Collation::Caseless).1, 2);
+    assert_eq!(graphemes("Ee\u{301}", Collation::Base).1, 1);
+    assert_eq!(graphemes("Ee", Collation::Caseless).1, 1);
+}
+
+#[test]
+fn test_tokens() {
+    let (subsequence, len) =
+        longest_lyndon_subsequence_tokens(b"the cat\tsat on\n the  mat ", false);
+    assert_eq!(
+        (subsequence.as_slice(), len),
+        (&b"cat sat on the mat"[..], 5)
+    );
+    assert_eq!(
+        longest_lyndon_subsequence_tokens(b" \n", false),
+        (Vec::new(), 0)
+    );
+    assert_eq!(longest_lyndon_subsequence_tokens(b"b B a", false).1, 2);
+    assert_eq!(longest_lyndon_subsequence_tokens(b"b B a", true).1, 1);
+    assert_eq!(
+        (fold_case('Ä'), fold_case('ß'), fold_case('1')),
+        ('ä', 'ß', '1')
+    );
+    // the pairs that simple case folding equates, but lowercasing does not
+    for (c, folded) in [
+        ('ſ', 's'),
+        ('ς', 'σ'),
+        ('µ', 'μ'),
+        ('ϑ', 'θ'),
+        ('ꭰ', 'Ꭰ'),
+        ('Ꭰ', 'Ꭰ'),
+    ] {
+        assert_eq!(fold_case(c), folded);
+    }
+    let order = Order::new(true, true, None, Unlisted::Error, false);
+    assert_eq!(order.key('ſ'), order.key('s'));
+    assert_eq!(order.key('ς'), order.key('Σ'));
+}
+
+#[test]
+fn test_char_filter() {
+    let filter = CharFilter {
+        include: Some(parse_char_set("a-z\\-\\x80-\\xff").unwrap()),
+        exclude: Some(parse_char_set("aeiou\\n-").unwrap()),
+        strip_whitespace: false,
+    };
+    assert_eq!(&*filter.bytes(b"Hello, w-orld!\n\xe4"), b"llwrld\xe4");
+    assert_eq!(filter.chars("Hällo wörld\u{100}"), "ällwörld");
+    assert_eq!(CharFilter::default().chars("Hello"), "Hello");
+    let strip = CharFilter {
+        strip_whitespace: true,
+        ..CharFilter::default()
+    };
+    assert_eq!(&*strip.bytes(b" a\tb\r\nc\x0c"), b"abc");
+    assert_eq!(strip.chars("ä\u{a0}b c"), "ä\u{a0}bc");
+    assert_eq!(strip.kept_bytes(b" a\tb\r\nc\x0c"), Some(vec![1, 3, 6]));
+    assert_eq!(strip.kept_chars("ä\u{a0}b c"), Some(vec![0, 1, 2, 4]));
+    assert_eq!(CharFilter::default().kept_bytes(b"ab"), None);
+    assert!(parse_char_set("z-a").is_err());
+    assert!(parse_char_set("\\q").is_err());
+    assert!(parse_char_set("-a-").unwrap().contains('-'));
+}
+
+#[test]
+fn test_alphabet() {
+    let alphabet = || Some(parse_alphabet("TGCA").unwrap());
+    let order = Order::new(false, false, alphabet(), Unlisted::Error, false);
+    assert!(order.key('T') < order.key('G') && order.key('C') < order.key('A'));
+    assert!(order.check("GATTACA".chars()).is_ok());
+    assert!(order.check("GATTACA\n".chars()).is_err());
+    let order = Order::new(false, false, alphabet(), Unlisted::Last, false);
+    assert!(order.check("GATTACA\n".chars()).is_ok());
+    assert!(order.key('A') < order.key('\n') && order.key('\n') < order.key('a'));
+    let order = Order::new(true, false, alphabet(), Unlisted::Error, true);
+    assert_eq!(order.key('a'), order.key('A'));
+    assert!(order.check("gattaca".chars()).is_ok());
+    assert!(order.key('t') > order.key('G') && order.key('C') > order.key('a'));
+    let order = Order::new(
+        true,
+        false,
+        Some(parse_alphabet("bBaA").unwrap()),
+        Unlisted::Last,
+        false,
+    );
+    assert!(order.key('B') < order.key('a') && order.key('A') < order.key('\n'));
+    assert!(parse_alphabet("ACGA").is_err());
+    assert_eq!(
+        parse_alphabet("a-c").unwrap(),
+        parse_alphabet("abc").unwrap()
+    );
+}
@@ -0,0 +1,113 @@
+use std::io::Write;
+
+use anyhow::Result;
+use longestlyndonsubseq::{longest_lyndon_subsequence_with_events, SearchEvent};
+use serde_json::{json, Value};
+
+use crate::output::escape;
+use crate::Symbols;
+
+/// the JSON object describing `event` of the search in the keys of `symbols`, with the characters at its positions
+fn to_json(symbols: &Symbols, event: SearchEvent) -> Value {
+    let character = |pos: usize| escape(symbols.get(pos..pos + 1));
+    match event {
+        SearchEvent::Push {
+            text_pos,
+            depth,
+            period,
+        } => json!({
+            "event": "push",
+            "text_pos": text_pos,
+            "char": character(text_pos),
+            "depth": depth,
+            "period": period,
+            "lyndon": period == depth,
+        }),
+        SearchEvent::Pop { text_pos, depth } => json!({
+            "event": "pop",
+            "text_pos": text_pos,
+            "char": character(text_pos),
+            "depth": depth,
+        }),
+        SearchEvent::Successor {
+            start,
+            bound,
+            inclusive,
+            found,
+        } => json!({
+            "event": "successor",
+            "start": start,
+            "bound": bound,
+            "bound_char": character(bound),
+            "inclusive": inclusive,
+            "found": found,
+            "found_char": found.map(character),
+        }),
+        SearchEvent::Larray { depth, text_pos } => json!({
+            "event": "larray",
+            "depth": depth,
+            "text_pos": text_pos,
+        }),
+        SearchEvent::Prune { text_pos, depth } => json!({
+            "event": "prune",
+            "text_pos": text_pos,
+            "char": character(text_pos),
+            "depth": depth,
+        }),
+        event => json!({ "event": format!("{event:?}") }),
+    }
+}
+
+/// writes every step of the search in the keys of `symbols` as a JSON object per line, labeled with `label`, followed by the result
+pub fn write_trace(out: &mut impl Write, label: &str, symbols: &Symbols) -> Result<()> {
+    let mut error = None;
+    let result = longest_lyndon_subsequence_with_events(&symbols.keys, |event| {
+        if error.is_some() {
+            return;
+        }
+        let mut object = to_json(symbols, event);
+        object["text"] = label.into();
+        let written = serde_json::to_writer(&mut *out, &object)
+            .map_err(anyhow::Error::from)
+            .and_then(|()| Ok(writeln!(out)?));
+        error = written.err();
+    });
+    if let Some(error) = error {
+        return Err(error);
+    }
+    let object = json!({
+        "text": label,
+        "event": "result",
+        "positions": result.positions(),
+        "length": result.len(),
+    });
+    serde_json::to_writer(&mut *out, &object)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+#[test]
+fn test_write_trace() {
+    let symbols = Symbols {
+        text: b"ab".to_vec(),
+        bounds: vec![0, 1, 2],
+        keys: vec![0, 1],
+    };
+    let mut out = Vec::new();
+    write_trace(&mut out, "t", &symbols).unwrap();
+    let events: Vec<Value> = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let kinds: Vec<&str> = events
+        .iter()
+        .map(|event| event["event"].as_str().unwrap())
+        .collect();
+    // no successor query above the length of the text
+    let expected = "push successor push larray pop successor pop push successor pop result";
+    assert_eq!(kinds.join(" "), expected);
+    assert_eq!(events[1]["found_char"], "b");
+    assert_eq!(events[10]["positions"], json!([0, 1]));
+    assert!(events.iter().all(|event| event["text"] == "t"));
+}
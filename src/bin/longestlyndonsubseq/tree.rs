@@ -0,0 +1,113 @@
+use std::io::Write;
+
+use anyhow::Result;
+use longestlyndonsubseq::{longest_lyndon_subsequence_with_events, SearchEvent};
+
+use crate::output::escape;
+use crate::Symbols;
+
+/// a node of the explored trie, i.e., a subsequence that is a prefix of a Lyndon word
+struct Node {
+    /// the node of the subsequence without its last character, the root for a single character
+    parent: usize,
+    text_pos: usize,
+    /// whether the subsequence is a Lyndon word
+    lyndon: bool,
+    /// whether the search skipped this node
+    pruned: bool,
+}
+
+/// writes the trie of subsequences that the search explores in the keys of `symbols` as a Graphviz graph named `name`,
+/// with the Lyndon subsequences doubly framed, the pruned branches dashed, and the path of the result in red
+pub fn write_tree(out: &mut impl Write, name: &str, symbols: &Symbols) -> Result<()> {
+    // the root is the empty subsequence
+    let mut nodes = vec![Node {
+        parent: 0,
+        text_pos: 0,
+        lyndon: false,
+        pruned: false,
+    }];
+    let mut path = vec![0];
+    let result = longest_lyndon_subsequence_with_events(&symbols.keys, |event| match event {
+        SearchEvent::Push {
+            text_pos,
+            depth,
+            period,
+        } => {
+            path.push(nodes.len());
+            nodes.push(Node {
+                parent: path[path.len() - 2],
+                text_pos,
+                lyndon: period == depth,
+                pruned: false,
+            });
+        }
+        SearchEvent::Pop { .. } => {
+            path.pop();
+        }
+        SearchEvent::Prune { text_pos, .. } => nodes.push(Node {
+            parent: path[path.len() - 1],
+            text_pos,
+            lyndon: false,
+            pruned: true,
+        }),
+        _ => {}
+    });
+    // the nodes of the result are the ones whose ancestors are, at its positions
+    let mut depths = vec![0; nodes.len()];
+    let mut in_result = vec![true; nodes.len()];
+    for i in 1..nodes.len() {
+        let node = &nodes[i];
+        depths[i] = depths[node.parent] + 1;
+        in_result[i] = in_result[node.parent]
+            && !node.pruned
+            && result.positions().get(depths[i] - 1) == Some(&node.text_pos);
+    }
+    writeln!(out, "digraph \"{}\" {{", quote(name))?;
+    writeln!(out, "  node [shape=box, fontname=\"monospace\"];")?;
+    writeln!(out, "  n0 [label=\"\", shape=point];")?;
+    for (i, node) in nodes.iter().enumerate().skip(1) {
+        let character = escape(symbols.get(node.text_pos..node.text_pos + 1));
+        let mut attributes = vec![format!(
+            "label=\"{}\\n{}\"",
+            quote(&character),
+            node.text_pos
+        )];
+        if node.lyndon {
+            attributes.push("peripheries=2".to_owned());
+        }
+        if node.pruned {
+            attributes.push("style=dashed, color=gray".to_owned());
+        }
+        if in_result[i] {
+            attributes.push("color=red".to_owned());
+        }
+        writeln!(out, "  n{i} [{}];", attributes.join(", "))?;
+        writeln!(out, "  n{} -> n{i};", node.parent)?;
+    }
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// `text` with the characters special in quoted Graphviz strings escaped
+fn quote(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[test]
+fn test_write_tree() {
+    let symbols = Symbols {
+        text: b"aab".to_vec(),
+        bounds: vec![0, 1, 2, 3],
+        keys: vec![0, 0, 1],
+    };
+    let mut out = Vec::new();
+    write_tree(&mut out, "a\"b", &symbols).unwrap();
+    let dot = String::from_utf8(out).unwrap();
+    assert!(dot.starts_with("digraph \"a\\\"b\" {\n"));
+    // aa, aab, ab and b below a
+    assert_eq!(dot.matches(" -> ").count(), 5);
+    assert!(dot.contains("n3 [label=\"b\\n2\", peripheries=2, color=red];"));
+    assert!(dot.contains("n4 [label=\"b\\n2\", peripheries=2];"));
+    assert!(dot.ends_with("}\n"));
+}
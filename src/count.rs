@@ -0,0 +1,394 @@
+use alloc::vec::Vec;
+use core::ops::Bound;
+
+use crate::search::{search, StackElement};
+use crate::util::{leftmost_distinct_symbols, NextOccurrenceIndex};
+use crate::LyndonResult;
+
+/// counter for [`count_longest_with`], e.g., `u64`, `u128`, or [`Modular`]
+pub trait Count {
+    /// the empty count
+    fn zero() -> Self;
+    /// adds one to the count
+    fn increment(&mut self);
+}
+
+impl Count for u64 {
+    fn zero() -> Self {
+        0
+    }
+
+    fn increment(&mut self) {
+        *self = self.checked_add(1).expect("count overflow");
+    }
+}
+
+impl Count for u128 {
+    fn zero() -> Self {
+        0
+    }
+
+    fn increment(&mut self) {
+        *self = self.checked_add(1).expect("count overflow");
+    }
+}
+
+/// a count modulo `M`, which has to be positive
+///
+/// ```compile_fail
+/// use longestlyndonsubseq::{count_longest_with, Modular};
+///
+/// count_longest_with::<Modular<0>, _>(b"acab");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Modular<const M: u64>(pub u64);
+
+impl<const M: u64> Count for Modular<M> {
+    fn zero() -> Self {
+        const { assert!(M > 0, "the modulus has to be positive") };
+        Modular(0)
+    }
+
+    fn increment(&mut self) {
+        self.0 = (self.0 + 1) % M;
+    }
+}
+
+/// the length of a longest Lyndon subsequence and the number of distinct longest Lyndon subsequences
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LongestCount<C = u128> {
+    /// length of a longest Lyndon subsequence
+    pub length: usize,
+    /// number of distinct words of that length that are Lyndon subsequences
+    pub count: C,
+}
+
+/// counts the distinct longest Lyndon subsequences of `text`
+///
+/// Subsequences spelling the same word are counted once, regardless of their number of occurrences.
+/// All of them are enumerated, so the running time is proportional to their number,
+/// see [`count_longest_with`] for other counters.
+///
+/// ```
+/// use longestlyndonsubseq::count_longest;
+///
+/// // "aab" and "acb"
+/// assert_eq!(count_longest(b"acab").length, 3);
+/// assert_eq!(count_longest(b"acab").count, 2);
+/// ```
+pub fn count_longest<T: Ord + Copy>(text: &[T]) -> LongestCount {
+    count_longest_with(text)
+}
+
+/// counts the distinct longest Lyndon subsequences of `text` with the counter `C`
+///
+/// ```
+/// use longestlyndonsubseq::{count_longest_with, Modular};
+///
+/// assert_eq!(count_longest_with::<Modular<2>, _>(b"acab").count, Modular(0));
+/// ```
+pub fn count_longest_with<C: Count, T: Ord + Copy>(text: &[T]) -> LongestCount<C> {
+    let mut optimal = LyndonSubsequences::longest(text);
+    let mut count = C::zero();
+    while optimal.advance() {
+        count.increment();
+    }
+    LongestCount {
+        length: optimal.max_len,
+        count,
+    }
+}
+
+/// lazily enumerates the distinct longest Lyndon subsequences of `text`, yielding their text positions
+///
+/// Each yielded subsequence spells a different word; its positions are the leftmost occurrence of that word.
+/// The words are yielded in lexicographic order.
+///
+/// ```
+/// use longestlyndonsubseq::iter_all_longest;
+///
+/// let mut all = iter_all_longest(b"acab");
+/// assert_eq!(all.length(), 3);
+/// assert_eq!(all.next(), Some(vec![0, 2, 3])); // "aab"
+/// assert_eq!(all.next(), Some(vec![0, 1, 3])); // "acb"
+/// assert_eq!(all.next(), None);
+/// ```
+pub fn iter_all_longest<T: Ord + Copy>(text: &[T]) -> AllLongest<'_, T> {
+    AllLongest(LyndonSubsequences::longest(text))
+}
+
+/// iterator over the distinct longest Lyndon subsequences of a text, created by [`iter_all_longest`]
+pub struct AllLongest<'a, T>(LyndonSubsequences<'a, T>);
+
+impl<T: Ord + Copy> AllLongest<'_, T> {
+    /// length of the longest Lyndon subsequences
+    pub fn length(&self) -> usize {
+        self.0.max_len
+    }
+}
+
+impl<T: Ord + Copy> Iterator for AllLongest<'_, T> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0
+            .advance()
+            .then(|| self.0.stack.iter().map(|el| el.text_pos).collect())
+    }
+}
+
+/// lazily enumerates the distinct Lyndon subsequences of `text` with at most `max_len` characters, yielding their text positions
+///
+/// Each yielded subsequence spells a different word; its positions are the leftmost occurrence of that word.
+/// The words are yielded in lexicographic order, and their number can grow exponentially with `max_len`.
+///
+/// ```
+/// use longestlyndonsubseq::iter_lyndon_subsequences;
+///
+/// let all: Vec<Vec<usize>> = iter_lyndon_subsequences(b"acab", 2).collect();
+/// // "a", "ab", "ac", "b", "c"
+/// assert_eq!(all, [vec![0], vec![0, 3], vec![0, 1], vec![3], vec![1]]);
+/// ```
+pub fn iter_lyndon_subsequences<T: Ord + Copy>(
+    text: &[T],
+    max_len: usize,
+) -> LyndonSubsequences<'_, T> {
+    LyndonSubsequences::new(text, 0, max_len)
+}
+
+/// iterator over the distinct Lyndon subsequences of a text up to a length, created by [`iter_lyndon_subsequences`]
+pub struct LyndonSubsequences<'a, T> {
+    text: &'a [T],
+    index: NextOccurrenceIndex<T>,
+    max_len: usize,
+    /// the subsequences shorter than this length are skipped, and so are the branches that cannot reach it
+    min_len: usize,
+    /// leftmost occurrences of the distinct characters, in reverse order
+    roots: Vec<usize>,
+    stack: Vec<StackElement>,
+    /// set after an upward move: the next child must have a larger edge label
+    lastchildedgelabel: Option<T>,
+}
+
+impl<'a, T: Ord + Copy> LyndonSubsequences<'a, T> {
+    fn new(text: &'a [T], min_len: usize, max_len: usize) -> Self {
+        let mut roots = leftmost_distinct_symbols(text);
+        roots.reverse();
+        LyndonSubsequences {
+            text,
+            index: NextOccurrenceIndex::new(text),
+            max_len,
+            min_len,
+            roots,
+            stack: Vec::new(),
+            lastchildedgelabel: None,
+        }
+    }
+
+    /// the traversal stopping at each distinct longest Lyndon subsequence of `text`
+    fn longest(text: &'a [T]) -> Self {
+        let mut length = 0;
+        search(
+            text,
+            &T::cmp,
+            leftmost_distinct_symbols(text),
+            &mut |stack: &[StackElement]| length = length.max(stack.len()),
+        );
+        LyndonSubsequences::new(text, length, length)
+    }
+
+    /// moves to the next Lyndon subsequence on the stack; returns `false` if there is none left
+    fn advance(&mut self) -> bool {
+        let text = self.text;
+        if self.max_len == 0 {
+            return false;
+        }
+        loop {
+            let Some(top) = self.stack.last().copied() else {
+                let Some(root) = self.roots.pop() else {
+                    return false;
+                };
+                self.lastchildedgelabel = None;
+                if text.len() - root < self.min_len {
+                    continue;
+                }
+                self.stack.push(StackElement {
+                    text_pos: root,
+                    period: 1,
+                });
+                if self.min_len <= 1 {
+                    return true;
+                }
+                continue;
+            };
+            if self.stack.len() == self.max_len {
+                // leaf of the trie: the Lyndon subsequence returned previously, if any
+                self.lastchildedgelabel = Some(text[self.stack.pop().unwrap().text_pos]);
+                continue;
+            }
+            let immature_character = text[self.stack[self.stack.len() - top.period].text_pos];
+            let bound = match &self.lastchildedgelabel {
+                Some(label) => Bound::Excluded(label),
+                None => Bound::Included(&immature_character),
+            };
+            let Some(i) = self.index.successor(top.text_pos + 1, bound) else {
+                self.lastchildedgelabel = Some(text[top.text_pos]);
+                self.stack.pop();
+                continue;
+            };
+            let subsequence_length = self.stack.len() + 1;
+            if subsequence_length + (text.len() - 1 - i) < self.min_len {
+                // too few characters left to reach the minimum length
+                self.lastchildedgelabel = Some(text[i]);
+                continue;
+            }
+            let period = if immature_character == text[i] {
+                top.period
+            } else {
+                subsequence_length
+            };
+            self.stack.push(StackElement {
+                text_pos: i,
+                period,
+            });
+            self.lastchildedgelabel = None;
+            if period == subsequence_length && subsequence_length >= self.min_len {
+                return true;
+            }
+        }
+    }
+}
+
+impl<T: Ord + Copy> Iterator for LyndonSubsequences<'_, T> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.advance()
+            .then(|| self.stack.iter().map(|el| el.text_pos).collect())
+    }
+}
+
+/// computes the `k` longest distinct Lyndon subsequences of `text`, longest first
+///
+/// Subsequences of equal length are ordered lexicographically, and each is at the leftmost occurrence of its word.
+/// Fewer are returned if `text` has fewer distinct Lyndon subsequences.
+///
+/// ```
+/// use longestlyndonsubseq::top_k_longest;
+///
+/// let results = top_k_longest(b"acab", 3);
+/// let words: Vec<&[u8]> = results.iter().map(|result| result.subsequence()).collect();
+/// assert_eq!(words, [&b"aab"[..], b"acb", b"ab"]);
+/// ```
+pub fn top_k_longest<T: Ord + Copy>(text: &[T], k: usize) -> Vec<LyndonResult<T>> {
+    let mut all = iter_lyndon_subsequences(text, usize::MAX);
+    // the best ones so far, longest first, and in the order found, which is lexicographic, among equally long ones
+    let mut best: Vec<Vec<StackElement>> = Vec::new();
+    while k > 0 && all.advance() {
+        let len = all.stack.len();
+        best.insert(
+            best.partition_point(|stack| stack.len() >= len),
+            all.stack.clone(),
+        );
+        best.truncate(k);
+        if best.len() == k {
+            // only longer subsequences can replace the shortest one
+            all.min_len = best[k - 1].len() + 1;
+        }
+    }
+    best.into_iter()
+        .map(|stack| LyndonResult::from_stack(text, &stack))
+        .collect()
+}
+
+/// computes the `k` longest distinct Lyndon subsequences of `text` with respect to the order of the keys computed by `key`, see [`top_k_longest`]
+///
+/// ```
+/// use longestlyndonsubseq::top_k_longest_by_key;
+///
+/// let results = top_k_longest_by_key(b"abcab", 2, |&c| std::cmp::Reverse(c));
+/// assert_eq!(results[0].subsequence(), b"cab");
+/// ```
+pub fn top_k_longest_by_key<T: Copy, K: Ord + Copy>(
+    text: &[T],
+    k: usize,
+    key: impl Fn(&T) -> K,
+) -> Vec<LyndonResult<T>> {
+    let keys: Vec<K> = text.iter().map(key).collect();
+    top_k_longest(&keys, k)
+        .into_iter()
+        .map(|result| result.map_text(text))
+        .collect()
+}
+
+#[test]
+fn test_count_longest() {
+    use crate::is_lyndon;
+    use crate::reference::all_words;
+    use alloc::collections::BTreeSet;
+
+    // compare with all subsequences of all words of length at most 8 over {0, 1, 2}
+    for len in 0..=8 {
+        for text in all_words(3, len) {
+            let mut lyndon = BTreeSet::new();
+            for mask in 1..1usize << text.len() {
+                let word: Vec<u8> = (0..text.len())
+                    .filter(|i| mask >> i & 1 == 1)
+                    .map(|i| text[i])
+                    .collect();
+                if is_lyndon(&word) {
+                    lyndon.insert(word);
+                }
+            }
+            let length = lyndon.iter().map(Vec::len).max().unwrap_or(0);
+            let count = lyndon.iter().filter(|w| w.len() == length).count() as u128;
+            assert_eq!(
+                count_longest(&text),
+                LongestCount { length, count },
+                "{text:?}"
+            );
+        }
+    }
+    assert_eq!(count_longest::<u8>(b"").count, 0);
+}
+
+#[test]
+fn test_lyndon_subsequences() {
+    use crate::is_lyndon;
+    use crate::reference::all_words;
+    use alloc::collections::BTreeSet;
+
+    // compare with all subsequences of all words of length at most 7 over {0, 1, 2}
+    for len in 0..=7 {
+        for text in all_words(3, len) {
+            let mut lyndon = BTreeSet::new();
+            for mask in 1..1usize << text.len() {
+                let word: Vec<u8> = (0..text.len())
+                    .filter(|i| mask >> i & 1 == 1)
+                    .map(|i| text[i])
+                    .collect();
+                if is_lyndon(&word) {
+                    lyndon.insert(word);
+                }
+            }
+            let mut longest: Vec<&Vec<u8>> = lyndon.iter().collect();
+            longest.sort_by_key(|word| core::cmp::Reverse(word.len()));
+            for k in [0, 1, 3] {
+                let words: Vec<Vec<u8>> = top_k_longest(&text, k)
+                    .iter()
+                    .map(|result| result.to_vec())
+                    .collect();
+                let expected: Vec<&Vec<u8>> = longest.iter().copied().take(k).collect();
+                assert_eq!(words.iter().collect::<Vec<_>>(), expected, "{text:?}");
+            }
+            for max_len in 0..=4 {
+                let expected: Vec<&Vec<u8>> =
+                    lyndon.iter().filter(|w| w.len() <= max_len).collect();
+                let words: Vec<Vec<u8>> = iter_lyndon_subsequences(&text, max_len)
+                    .map(|positions| positions.iter().map(|&i| text[i]).collect())
+                    .collect();
+                assert_eq!(words.iter().collect::<Vec<_>>(), expected, "{text:?}");
+            }
+        }
+    }
+}
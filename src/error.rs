@@ -0,0 +1,31 @@
+use thiserror::Error;
+
+use crate::LyndonResult;
+
+/// errors reported by this crate
+#[derive(Debug, Error)]
+pub enum LyndonError {
+    /// reading the input failed
+    #[cfg(feature = "std")]
+    #[error("cannot read the input")]
+    Io(#[from] std::io::Error),
+
+    /// the input does not fit into the address space of this platform
+    #[error("input of {0} bytes exceeds the addressable memory")]
+    InputTooLarge(u64),
+
+    /// the computation was cancelled
+    #[error(transparent)]
+    Cancelled(#[from] Cancelled),
+}
+
+/// the computation was cancelled before it finished
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("the computation was cancelled")]
+pub struct Cancelled<T = u8> {
+    /// the longest Lyndon subsequence found before the cancellation
+    pub best: LyndonResult<T>,
+}
+
+/// shorthand for results with a [`LyndonError`]
+pub type Result<T, E = LyndonError> = core::result::Result<T, E>;
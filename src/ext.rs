@@ -0,0 +1,41 @@
+use alloc::vec::Vec;
+
+use crate::{factorization, LyndonResult};
+
+/// Lyndon word routines as methods on slices
+///
+/// ```
+/// use longestlyndonsubseq::LyndonExt;
+///
+/// assert_eq!(b"bccadbaccbcd".longest_lyndon_subsequence().subsequence(), b"bccbccbcd");
+/// assert_eq!(b"bccadbaccbcd".lyndon_factorization(), [&b"bcc"[..], b"adb", b"accbcd"]);
+/// ```
+pub trait LyndonExt<T> {
+    /// computes a longest Lyndon subsequence, see [`longest_lyndon_subsequence`](crate::longest_lyndon_subsequence)
+    fn longest_lyndon_subsequence(&self) -> LyndonResult<T>;
+
+    /// splits the text into its Lyndon factors, i.e., lexicographically non-increasing Lyndon words
+    fn lyndon_factorization(&self) -> Vec<&[T]>;
+}
+
+impl<T: Ord + Copy> LyndonExt<T> for [T] {
+    fn longest_lyndon_subsequence(&self) -> LyndonResult<T> {
+        crate::longest_lyndon_subsequence(self)
+    }
+
+    fn lyndon_factorization(&self) -> Vec<&[T]> {
+        factorization::lyndon_factors(self).collect()
+    }
+}
+
+#[test]
+fn test_lyndon_ext() {
+    let text = [3, 1, 2, 1, 2, 0];
+    assert_eq!(text.longest_lyndon_subsequence().subsequence(), [1, 1, 2]);
+    assert_eq!(
+        text.lyndon_factorization(),
+        [&[3][..], &[1, 2], &[1, 2], &[0]]
+    );
+    assert!(b"".lyndon_factorization().is_empty());
+    assert_eq!(b"aaa".lyndon_factorization(), [b"a", b"a", b"a"]);
+}
@@ -0,0 +1,235 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// computes the Lyndon factorization of `text` with Duval's algorithm in linear time
+///
+/// Returns the ranges of the factors, which are lexicographically non-increasing Lyndon words.
+///
+/// ```
+/// use longestlyndonsubseq::lyndon_factorization;
+///
+/// assert_eq!(lyndon_factorization(b"bccadbaccbcd"), [0..3, 3..6, 6..12]);
+/// ```
+pub fn lyndon_factorization<T: Ord>(text: &[T]) -> Vec<Range<usize>> {
+    let mut factors = Vec::new();
+    let mut k = 0;
+    while k < text.len() {
+        let mut i = k;
+        let mut j = k + 1;
+        while j < text.len() && text[i] <= text[j] {
+            if text[i] < text[j] {
+                i = k;
+            } else {
+                i += 1;
+            }
+            j += 1;
+        }
+        while k <= i {
+            factors.push(k..k + j - i);
+            k += j - i;
+        }
+    }
+    factors
+}
+
+/// iterates over the Lyndon factors of `text`, computing them lazily with Duval's algorithm
+///
+/// ```
+/// use longestlyndonsubseq::lyndon_factors;
+///
+/// assert!(lyndon_factors(b"bccadbaccbcd").eq([&b"bcc"[..], b"adb", b"accbcd"]));
+/// ```
+pub fn lyndon_factors<T: Ord>(text: &[T]) -> LyndonFactors<'_, T> {
+    LyndonFactors {
+        text,
+        k: 0,
+        repetition: None,
+    }
+}
+
+/// iterator over the Lyndon factors of a text, created by [`lyndon_factors`]
+#[derive(Debug, Clone)]
+pub struct LyndonFactors<'a, T> {
+    text: &'a [T],
+    /// start of the next factor
+    k: usize,
+    /// length and last starting position of a run of equal factors that is not yet exhausted
+    repetition: Option<(usize, usize)>,
+}
+
+impl<'a, T: Ord> Iterator for LyndonFactors<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((length, last_start)) = self.repetition {
+            if self.k <= last_start {
+                let factor = &self.text[self.k..self.k + length];
+                self.k += length;
+                return Some(factor);
+            }
+            self.repetition = None;
+        }
+        if self.k >= self.text.len() {
+            return None;
+        }
+        let k = self.k;
+        let mut i = k;
+        let mut j = k + 1;
+        while j < self.text.len() && self.text[i] <= self.text[j] {
+            if self.text[i] < self.text[j] {
+                i = k;
+            } else {
+                i += 1;
+            }
+            j += 1;
+        }
+        // the factors of length j - i start at k, k + (j - i), ..., up to (and including) position i
+        self.repetition = Some((j - i, i));
+        self.next()
+    }
+}
+
+/// computes the Lyndon array of `text`, storing at each position `i`
+/// the length of the longest Lyndon word starting at `i`
+///
+/// The longest Lyndon word starting at `i` ends right before the next suffix that is lexicographically smaller
+/// than the suffix starting at `i`. These next smaller suffixes are found with a stack on the ranks of the suffixes,
+/// which are sorted by prefix doubling in `O(n log n)` time.
+///
+/// ```
+/// use longestlyndonsubseq::lyndon_array;
+///
+/// assert_eq!(lyndon_array(b"bccadbacc"), [3, 1, 1, 3, 1, 1, 3, 1, 1]);
+/// ```
+pub fn lyndon_array<T: Ord>(text: &[T]) -> Vec<usize> {
+    let rank = suffix_ranks(text);
+    let mut lyndon = vec![0; text.len()];
+    // the positions right of `i` whose suffixes are smaller than all suffixes between them and `i`
+    let mut smaller: Vec<usize> = Vec::new();
+    for i in (0..text.len()).rev() {
+        while smaller.last().is_some_and(|&j| rank[j] > rank[i]) {
+            smaller.pop();
+        }
+        lyndon[i] = smaller.last().copied().unwrap_or(text.len()) - i;
+        smaller.push(i);
+    }
+    lyndon
+}
+
+/// the rank of each suffix of `text` among all its suffixes, i.e., the inverse suffix array
+///
+/// The suffixes are sorted by their first `k` characters for `k = 1, 2, 4, ...`,
+/// each round by a counting sort on the ranks of the previous one.
+fn suffix_ranks<T: Ord>(text: &[T]) -> Vec<usize> {
+    let n = text.len();
+    let mut suffixes: Vec<usize> = (0..n).collect();
+    suffixes.sort_by(|&a, &b| text[a].cmp(&text[b]));
+    let mut rank = vec![0; n];
+    for w in 1..n {
+        let (a, b) = (suffixes[w - 1], suffixes[w]);
+        rank[b] = rank[a] + usize::from(text[a] < text[b]);
+    }
+    let mut k = 1;
+    let mut by_second = Vec::with_capacity(n);
+    let mut count = vec![0; n];
+    let mut next_rank = vec![0; n];
+    while n > 0 && rank[suffixes[n - 1]] < n - 1 {
+        // ordered by the ranks of the suffixes k characters further right, where the ones without are the smallest
+        by_second.clear();
+        by_second.extend(n.saturating_sub(k)..n);
+        by_second.extend(suffixes.iter().filter(|&&i| i >= k).map(|&i| i - k));
+        count.iter_mut().for_each(|c| *c = 0);
+        for &i in &by_second {
+            count[rank[i]] += 1;
+        }
+        for r in 1..n {
+            count[r] += count[r - 1];
+        }
+        for &i in by_second.iter().rev() {
+            count[rank[i]] -= 1;
+            suffixes[count[rank[i]]] = i;
+        }
+        let key = |i: usize| (rank[i], rank.get(i + k).copied());
+        next_rank[suffixes[0]] = 0;
+        for w in 1..n {
+            let (a, b) = (suffixes[w - 1], suffixes[w]);
+            next_rank[b] = next_rank[a] + usize::from(key(a) != key(b));
+        }
+        core::mem::swap(&mut rank, &mut next_rank);
+        k *= 2;
+    }
+    rank
+}
+
+#[test]
+fn test_lyndon_array() {
+    use crate::is_lyndon;
+    use crate::reference::all_words;
+
+    let check = |text: &[u8]| {
+        let lyndon = lyndon_array(text);
+        for i in 0..text.len() {
+            let longest = (i + 1..=text.len())
+                .filter(|&end| is_lyndon(&text[i..end]))
+                .max()
+                .unwrap();
+            assert_eq!(lyndon[i], longest - i, "{text:?}");
+        }
+    };
+
+    for text in [
+        &b""[..],
+        b"a",
+        b"aaa",
+        b"ba",
+        b"abab",
+        b"bccadbaccbcd",
+        b"cbacbaabcab",
+    ] {
+        check(text);
+    }
+    for len in 0..=8 {
+        for text in all_words(3, len) {
+            check(&text);
+        }
+    }
+}
+
+#[test]
+fn test_lyndon_array_power() {
+    // each suffix of a^n b is a Lyndon word, which the naive suffix comparisons found in quadratic time
+    let n = 10_000;
+    let mut text = vec![b'a'; n];
+    text.push(b'b');
+    let lyndon = lyndon_array(&text);
+    assert!((0..=n).all(|i| lyndon[i] == n + 1 - i));
+}
+
+#[test]
+fn test_lyndon_factorization() {
+    use crate::is_lyndon;
+
+    for text in [
+        &b""[..],
+        b"a",
+        b"aaa",
+        b"ba",
+        b"abab",
+        b"bccadbaccbcd",
+        b"cbacbaabcab",
+    ] {
+        let factors = lyndon_factorization(text);
+        assert!(lyndon_factors(text).eq(factors.iter().map(|factor| &text[factor.clone()])));
+        let mut end = 0;
+        for (i, factor) in factors.iter().enumerate() {
+            assert_eq!(factor.start, end);
+            end = factor.end;
+            assert!(is_lyndon(&text[factor.clone()]));
+            if i > 0 {
+                assert!(text[factors[i - 1].clone()] >= text[factor.clone()]);
+            }
+        }
+        assert_eq!(end, text.len());
+    }
+}
@@ -0,0 +1,112 @@
+//! C interface, see `include/lls.h`
+
+use alloc::boxed::Box;
+use core::ffi::c_int;
+use core::ptr;
+use std::panic;
+
+use crate::longest_lyndon_subsequence_bytes;
+
+/// the computation succeeded
+pub const LLS_OK: c_int = 0;
+/// a required pointer argument was null
+pub const LLS_NULL_POINTER: c_int = -1;
+/// the computation panicked
+pub const LLS_INTERNAL_ERROR: c_int = -2;
+
+/// a longest Lyndon subsequence, owned by the library until passed to [`lls_free`]
+#[repr(C)]
+pub struct LlsResult {
+    /// length of the subsequence
+    pub length: usize,
+    /// the `length` text positions of the subsequence
+    pub positions: *mut usize,
+    /// the `length` characters of the subsequence
+    pub subsequence: *mut u8,
+}
+
+/// computes a longest Lyndon subsequence of the `length` bytes at `text` and stores it in `result`
+///
+/// Returns [`LLS_OK`] on success, in which case `result` has to be released with [`lls_free`].
+///
+/// # Safety
+/// `text` must point to `length` readable bytes (or may be null if `length` is 0),
+/// and `result` must point to writable memory for an [`LlsResult`].
+#[no_mangle]
+pub unsafe extern "C" fn lls_compute(
+    text: *const u8,
+    length: usize,
+    result: *mut LlsResult,
+) -> c_int {
+    if result.is_null() || (text.is_null() && length > 0) {
+        return LLS_NULL_POINTER;
+    }
+    let text = if length == 0 {
+        &[][..]
+    } else {
+        core::slice::from_raw_parts(text, length)
+    };
+    let Ok(lyndon) = panic::catch_unwind(|| longest_lyndon_subsequence_bytes(text)) else {
+        return LLS_INTERNAL_ERROR;
+    };
+    let positions = Box::<[usize]>::from(lyndon.positions());
+    let subsequence = lyndon.into_subsequence().into_boxed_slice();
+    result.write(LlsResult {
+        length: positions.len(),
+        positions: Box::into_raw(positions) as *mut usize,
+        subsequence: Box::into_raw(subsequence) as *mut u8,
+    });
+    LLS_OK
+}
+
+/// releases the memory of a result filled by [`lls_compute`]; the fields are reset afterwards
+///
+/// # Safety
+/// `result` must be null or point to an [`LlsResult`] filled by [`lls_compute`] that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn lls_free(result: *mut LlsResult) {
+    let Some(result) = result.as_mut() else {
+        return;
+    };
+    if !result.positions.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            result.positions,
+            result.length,
+        )));
+    }
+    if !result.subsequence.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            result.subsequence,
+            result.length,
+        )));
+    }
+    result.length = 0;
+    result.positions = ptr::null_mut();
+    result.subsequence = ptr::null_mut();
+}
+
+#[test]
+fn test_ffi() {
+    let text = b"bccadbaccbcd";
+    let mut result = core::mem::MaybeUninit::<LlsResult>::uninit();
+    let mut result = unsafe {
+        assert_eq!(
+            lls_compute(text.as_ptr(), text.len(), result.as_mut_ptr()),
+            LLS_OK
+        );
+        result.assume_init()
+    };
+    unsafe {
+        assert_eq!(
+            core::slice::from_raw_parts(result.subsequence, result.length),
+            b"bccbccbcd"
+        );
+        assert_eq!(
+            core::slice::from_raw_parts(result.positions, result.length),
+            [0, 1, 2, 5, 7, 8, 9, 10, 11]
+        );
+        lls_free(&mut result);
+        assert!(result.positions.is_null());
+        assert_eq!(lls_compute(ptr::null(), 1, &mut result), LLS_NULL_POINTER);
+    }
+}
@@ -0,0 +1,71 @@
+use alloc::vec::Vec;
+
+use arbitrary::{Arbitrary, Unstructured};
+
+use crate::{longest_lyndon_subsequence, LyndonResult};
+
+/// maximum text length of a generated [`LyndonResult`]
+const MAX_RESULT_TEXT_LEN: usize = 16;
+
+/// a text of at most `MAX_LEN` characters over the first `SIGMA` lowercase letters,
+/// small enough for exhaustive cross-checks
+///
+/// ```
+/// use arbitrary::{Arbitrary, Unstructured};
+/// use longestlyndonsubseq::SmallText;
+///
+/// let mut u = Unstructured::new(&[5, 1, 2, 0, 1, 1]);
+/// let text = SmallText::<2, 8>::arbitrary(&mut u).unwrap();
+/// assert!(text.0.len() <= 8);
+/// assert!(text.0.iter().all(|c| b"ab".contains(c)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SmallText<const SIGMA: u8 = 3, const MAX_LEN: usize = 12>(pub Vec<u8>);
+
+impl<const SIGMA: u8, const MAX_LEN: usize> AsRef<[u8]> for SmallText<SIGMA, MAX_LEN> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<'a, const SIGMA: u8, const MAX_LEN: usize> Arbitrary<'a> for SmallText<SIGMA, MAX_LEN> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        if SIGMA == 0 {
+            return Ok(SmallText(Vec::new()));
+        }
+        let len = u.int_in_range(0..=MAX_LEN)?;
+        let text = (0..len)
+            .map(|_| Ok(b'a' + u.int_in_range(0..=SIGMA - 1)?))
+            .collect::<arbitrary::Result<_>>()?;
+        Ok(SmallText(text))
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, Some(core::mem::size_of::<usize>() + MAX_LEN))
+    }
+}
+
+/// the longest Lyndon subsequence of an arbitrary text of at most 16 characters
+impl<'a, T: Arbitrary<'a> + Ord + Copy> Arbitrary<'a> for LyndonResult<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.int_in_range(0..=MAX_RESULT_TEXT_LEN)?;
+        let text = (0..len)
+            .map(|_| T::arbitrary(u))
+            .collect::<arbitrary::Result<Vec<T>>>()?;
+        Ok(longest_lyndon_subsequence(&text))
+    }
+}
+
+#[test]
+fn test_arbitrary() {
+    use crate::is_lyndon;
+
+    let bytes: Vec<u8> = (0..=255u8).rev().cycle().take(4096).collect();
+    let mut u = Unstructured::new(&bytes);
+    while !u.is_empty() {
+        let text = SmallText::<3, 10>::arbitrary(&mut u).unwrap();
+        assert!(text.0.len() <= 10 && text.0.iter().all(|c| b"abc".contains(c)));
+        let result = LyndonResult::<char>::arbitrary(&mut u).unwrap();
+        assert!(result.is_empty() || is_lyndon(result.subsequence()));
+    }
+}
@@ -0,0 +1,72 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::error::{LyndonError, Result};
+
+/// reads a file into a u8 vector
+/// - `prefix_length` : the prefix in bytes to read from `filename`. `None` means to read the entire file
+pub fn file2byte_vector<P: AsRef<Path>>(path: P, prefix_length: Option<u64>) -> Result<Vec<u8>> {
+    file_range2byte_vector(path, 0, prefix_length)
+}
+
+/// reads the bytes of a file starting at `offset` into a u8 vector
+/// - `length` : the number of bytes to read. `None` means to read until the end of the file
+pub fn file_range2byte_vector<P: AsRef<Path>>(
+    path: P,
+    offset: u64,
+    length: Option<u64>,
+) -> Result<Vec<u8>> {
+    let mut f = fs::File::open(&path)?;
+    let metadata = fs::metadata(&path)?;
+    let offset = offset.min(metadata.len());
+    let num_file_bytes = metadata.len() - offset;
+    let buffer_length = length
+        .map(|v| v.min(num_file_bytes))
+        .unwrap_or(num_file_bytes);
+    f.seek(SeekFrom::Start(offset))?;
+    let buffer_length =
+        usize::try_from(buffer_length).map_err(|_| LyndonError::InputTooLarge(buffer_length))?;
+    let mut buffer = vec![0u8; buffer_length];
+    f.read_exact(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// reads everything from `reader` into a u8 vector
+/// - `limit` : the maximum number of bytes to read. `None` means to read until the end of the input
+pub fn from_reader(reader: impl Read, limit: Option<u64>) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    reader
+        .take(limit.unwrap_or(u64::MAX))
+        .read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
+#[test]
+fn test_from_reader() {
+    let input = std::io::Cursor::new(b"bccadbaccbcd");
+    assert_eq!(from_reader(input.clone(), None).unwrap(), b"bccadbaccbcd");
+    assert_eq!(from_reader(input.clone(), Some(4)).unwrap(), b"bcca");
+    assert_eq!(from_reader(input, Some(100)).unwrap(), b"bccadbaccbcd");
+}
+
+#[test]
+fn test_file_range() {
+    let path = std::env::temp_dir().join(format!("lls-range-{}.txt", std::process::id()));
+    fs::write(&path, b"bccadbaccbcd").unwrap();
+    assert_eq!(file_range2byte_vector(&path, 3, Some(4)).unwrap(), b"adba");
+    assert_eq!(file_range2byte_vector(&path, 9, None).unwrap(), b"bcd");
+    assert_eq!(file_range2byte_vector(&path, 20, Some(4)).unwrap(), b"");
+    assert_eq!(file2byte_vector(&path, Some(3)).unwrap(), b"bcc");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_missing_file() {
+    assert!(matches!(
+        file2byte_vector("/nonexistent/input.txt", None),
+        Err(LyndonError::Io(_))
+    ));
+}
@@ -0,0 +1,579 @@
+//! Computes the longest Lyndon subsequence of a text.
+//!
+//! The algorithm traverses the trie of all Lyndon subsequences of the text in lexicographic order,
+//! pruning branches that cannot lead to a longer Lyndon subsequence.
+//! See Bannai et al., "Computing Longest (Common) Lyndon Subsequences", IWOCA 2022.
+//!
+//! ```
+//! use longestlyndonsubseq::longest_lyndon_subsequence;
+//!
+//! let result = longest_lyndon_subsequence(b"bccadbaccbcd");
+//! assert_eq!(result.subsequence(), b"bccbccbcd");
+//! assert_eq!(result.positions(), [0, 1, 2, 5, 7, 8, 9, 10, 11]);
+//! ```
+//!
+//! Without the default `std` feature, the crate is `no_std` and only needs `alloc`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::sync::atomic::AtomicBool;
+
+mod count;
+mod error;
+mod ext;
+mod factorization;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "arbitrary")]
+mod fuzzing;
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "tokio")]
+mod nonblocking;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod predicates;
+mod progress;
+#[cfg(feature = "python")]
+mod python;
+mod range;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod reference;
+mod result;
+mod search;
+mod solver;
+pub mod util;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use count::{
+    count_longest, count_longest_with, iter_all_longest, iter_lyndon_subsequences, top_k_longest,
+    top_k_longest_by_key, AllLongest, Count, LongestCount, LyndonSubsequences, Modular,
+};
+pub use error::{Cancelled, LyndonError, Result};
+pub use ext::LyndonExt;
+pub use factorization::{lyndon_array, lyndon_factorization, lyndon_factors, LyndonFactors};
+#[cfg(feature = "arbitrary")]
+pub use fuzzing::SmallText;
+#[cfg(feature = "std")]
+pub use io::{file2byte_vector, file_range2byte_vector, from_reader};
+#[cfg(feature = "tokio")]
+pub use nonblocking::{
+    longest_lyndon_subsequence_async, longest_lyndon_subsequence_async_with_progress,
+};
+#[cfg(feature = "rayon")]
+pub use parallel::par_longest_lyndon_subsequence;
+pub use predicates::{is_lyndon, is_necklace, is_pre_lyndon, is_primitive};
+pub use progress::Progress;
+pub use range::RangeQuery;
+pub use result::{LyndonResult, LyndonStructure};
+pub use search::SearchEvent;
+pub use solver::{BatchSolver, SearchStats, Solver, TieBreak};
+
+use progress::ReportProgress;
+use search::{
+    keep_longest, search, search_with_successor, Bounded, CancelOnFlag, ReportEvents,
+    SearchBuffers, StackElement,
+};
+use util::{leftmost_distinct_characters, leftmost_distinct_symbols_by, SuccessorIndex};
+
+/// computes a longest Lyndon subsequence of `text` over an arbitrary ordered alphabet
+///
+/// For byte strings, [`longest_lyndon_subsequence_bytes`] is faster.
+pub fn longest_lyndon_subsequence<T: Ord + Copy>(text: &[T]) -> LyndonResult<T> {
+    longest_lyndon_subsequence_by(text, T::cmp)
+}
+
+/// computes a longest Lyndon subsequence of `text` with respect to the alphabet order `cmp`
+///
+/// `cmp` has to be a total order; characters comparing [`Ordering::Equal`] are treated as the same character.
+///
+/// ```
+/// use longestlyndonsubseq::longest_lyndon_subsequence_by;
+///
+/// // Lyndon with respect to the reversed byte order
+/// let result = longest_lyndon_subsequence_by(b"abcab", |a, b| b.cmp(a));
+/// assert_eq!(result.subsequence(), b"cab");
+/// ```
+pub fn longest_lyndon_subsequence_by<T: Copy>(
+    text: &[T],
+    cmp: impl Fn(&T, &T) -> Ordering,
+) -> LyndonResult<T> {
+    let mut longest = Vec::new();
+    let starting_positions = leftmost_distinct_symbols_by(text, &cmp);
+    search(
+        text,
+        &cmp,
+        starting_positions,
+        &mut |stack: &[StackElement]| keep_longest(&mut longest, stack),
+    );
+    LyndonResult::from_stack(text, &longest)
+}
+
+/// computes a longest Lyndon subsequence of `text` with respect to the order of the keys computed by `key`
+///
+/// ```
+/// use longestlyndonsubseq::longest_lyndon_subsequence_by_key;
+///
+/// // rank of each nucleotide in the order A < C < G < T
+/// let rank = |c: &u8| b"ACGT".iter().position(|x| x == c);
+/// let result = longest_lyndon_subsequence_by_key(b"GATTACA", rank);
+/// assert_eq!(result.subsequence(), b"ATTC");
+/// ```
+pub fn longest_lyndon_subsequence_by_key<T: Copy, K: Ord>(
+    text: &[T],
+    key: impl Fn(&T) -> K,
+) -> LyndonResult<T> {
+    longest_lyndon_subsequence_by(text, |a, b| key(a).cmp(&key(b)))
+}
+
+/// computes a longest Lyndon subsequence of `text` among those of length at most `max_len`
+///
+/// ```
+/// use longestlyndonsubseq::longest_lyndon_subsequence_bounded;
+///
+/// let result = longest_lyndon_subsequence_bounded(b"bccadbaccbcd", 4);
+/// assert_eq!(result.len(), 4);
+/// assert!(longestlyndonsubseq::is_lyndon(result.subsequence()));
+/// ```
+pub fn longest_lyndon_subsequence_bounded<T: Ord + Copy>(
+    text: &[T],
+    max_len: usize,
+) -> LyndonResult<T> {
+    let mut longest = Vec::new();
+    search(
+        text,
+        &T::cmp,
+        leftmost_distinct_symbols_by(text, &T::cmp),
+        &mut Bounded {
+            inner: |stack: &[StackElement]| keep_longest(&mut longest, stack),
+            max_len,
+        },
+    );
+    LyndonResult::from_stack(text, &longest)
+}
+
+/// computes a longest Lyndon subsequence of `text`, answering the successor queries of the search with `index`
+///
+/// `index` has to be built on `text`, e.g., trading speed for space with a [`util::WaveletTree`]
+/// instead of the default scan of the text.
+///
+/// ```
+/// use longestlyndonsubseq::longest_lyndon_subsequence_with_index;
+/// use longestlyndonsubseq::util::{NextOccurrenceIndex, WaveletTree};
+///
+/// let text = b"bccadbaccbcd";
+/// let result = longest_lyndon_subsequence_with_index(text, &NextOccurrenceIndex::new(text));
+/// assert_eq!(result.subsequence(), b"bccbccbcd");
+/// let result = longest_lyndon_subsequence_with_index(text, &WaveletTree::new(text));
+/// assert_eq!(result.subsequence(), b"bccbccbcd");
+/// ```
+pub fn longest_lyndon_subsequence_with_index<T: Ord + Copy>(
+    text: &[T],
+    index: &(impl SuccessorIndex<T> + ?Sized),
+) -> LyndonResult<T> {
+    let mut longest = Vec::new();
+    search_with_successor(
+        &mut SearchBuffers::default(),
+        text,
+        &T::cmp,
+        |start, bound| index.successor(start, bound),
+        leftmost_distinct_symbols_by(text, &T::cmp),
+        &mut |stack: &[StackElement]| keep_longest(&mut longest, stack),
+    );
+    LyndonResult::from_stack(text, &longest)
+}
+
+/// the most entries of the table of [`longest_lyndon_subsequence_bytes`], i.e., 32 MiB on 64-bit platforms
+const NEXT_OCCURRENCE_LIMIT: usize = 1 << 22;
+
+/// computes a longest Lyndon subsequence of the byte string `text`
+///
+/// Same as [`longest_lyndon_subsequence`], but answering the successor queries of the search
+/// with a [`util::NextOccurrenceIndex`] of the distinct bytes instead of scanning the text,
+/// which is up to 2.5 times faster on short random texts.
+/// The table has `(n + 1) σ` entries for `σ` distinct bytes, so texts for which it would exceed 32 MiB are scanned.
+pub fn longest_lyndon_subsequence_bytes(text: &[u8]) -> LyndonResult {
+    let sigma = leftmost_distinct_characters(text).count();
+    if (text.len() + 1).saturating_mul(sigma) <= NEXT_OCCURRENCE_LIMIT {
+        return longest_lyndon_subsequence_with_index(text, &util::NextOccurrenceIndex::new(text));
+    }
+    let mut longest = Vec::new();
+    search(
+        text,
+        &u8::cmp,
+        leftmost_distinct_characters(text),
+        &mut |stack: &[StackElement]| keep_longest(&mut longest, stack),
+    );
+    LyndonResult::from_stack(text, &longest)
+}
+
+/// computes a longest Lyndon subsequence of each of the byte strings `texts`
+///
+/// Same as calling [`longest_lyndon_subsequence_bytes`] on each text, but reusing the working memory of the search,
+/// see also [`BatchSolver`] for processing texts one at a time.
+///
+/// ```
+/// use longestlyndonsubseq::longest_lyndon_subsequences;
+///
+/// let results = longest_lyndon_subsequences(&[&b"bccadbaccbcd"[..], b"aaaba"]);
+/// assert_eq!(results[0].subsequence(), b"bccbccbcd");
+/// assert_eq!(results[1].subsequence(), b"aaab");
+/// ```
+pub fn longest_lyndon_subsequences(
+    texts: impl IntoIterator<Item = impl AsRef<[u8]>>,
+) -> Vec<LyndonResult> {
+    let mut solver = BatchSolver::new();
+    texts
+        .into_iter()
+        .map(|text| solver.solve(text.as_ref()))
+        .collect()
+}
+
+/// computes only the length of a longest Lyndon subsequence of the byte string `text`
+///
+/// Cheaper than [`longest_lyndon_subsequence_bytes`] since no witness has to be recorded.
+/// The working memory of the search is allocated for each call,
+/// [`BatchSolver::solve_len`] reuses it for many texts.
+pub fn longest_lyndon_subsequence_len(text: &[u8]) -> usize {
+    BatchSolver::new().solve_len(text)
+}
+
+/// computes a longest Lyndon subsequence of `text` while reporting every Lyndon subsequence visited by the search
+///
+/// `visitor` receives the text positions of each Lyndon subsequence the search confirms,
+/// in the order of traversal. Subsequences in pruned branches of the search are not reported.
+///
+/// ```
+/// use longestlyndonsubseq::longest_lyndon_subsequence_with_visitor;
+///
+/// let mut visited = Vec::new();
+/// let result = longest_lyndon_subsequence_with_visitor(b"aab", |positions| visited.push(positions.to_vec()));
+/// assert_eq!(result.subsequence(), b"aab");
+/// assert_eq!(visited, [vec![0], vec![0, 1, 2], vec![0, 2], vec![2]]);
+/// ```
+pub fn longest_lyndon_subsequence_with_visitor<T: Ord + Copy>(
+    text: &[T],
+    mut visitor: impl FnMut(&[usize]),
+) -> LyndonResult<T> {
+    let mut longest = Vec::new();
+    let mut positions = Vec::new();
+    search(
+        text,
+        &T::cmp,
+        leftmost_distinct_symbols_by(text, &T::cmp),
+        &mut |stack: &[StackElement]| {
+            positions.clear();
+            positions.extend(stack.iter().map(|el| el.text_pos));
+            visitor(&positions);
+            keep_longest(&mut longest, stack)
+        },
+    );
+    LyndonResult::from_stack(text, &longest)
+}
+
+/// computes a longest Lyndon subsequence of `text` while reporting every step of the search to `callback`
+///
+/// Unlike [`longest_lyndon_subsequence_with_visitor`], `callback` also sees the prefixes of Lyndon words
+/// that the search extends, its backtracking, and the branches it prunes, e.g., to draw the explored trie.
+///
+/// ```
+/// use longestlyndonsubseq::{longest_lyndon_subsequence_with_events, SearchEvent};
+///
+/// let mut pushed = Vec::new();
+/// let result = longest_lyndon_subsequence_with_events(b"aab", |event| {
+///     if let SearchEvent::Push { text_pos, .. } = event {
+///         pushed.push(text_pos);
+///     }
+/// });
+/// assert_eq!(result.subsequence(), b"aab");
+/// assert_eq!(pushed, [0, 1, 2, 2, 2]);
+/// ```
+pub fn longest_lyndon_subsequence_with_events<T: Ord + Copy>(
+    text: &[T],
+    callback: impl FnMut(SearchEvent),
+) -> LyndonResult<T> {
+    let mut longest = Vec::new();
+    search(
+        text,
+        &T::cmp,
+        leftmost_distinct_symbols_by(text, &T::cmp),
+        &mut ReportEvents {
+            longest: &mut longest,
+            callback,
+        },
+    );
+    LyndonResult::from_stack(text, &longest)
+}
+
+/// computes a longest Lyndon subsequence of `text`, aborting once `cancel` is set
+///
+/// The flag is checked periodically, so the computation stops shortly after it has been set,
+/// returning the longest Lyndon subsequence found so far as [`Cancelled::best`].
+///
+/// ```
+/// use std::sync::atomic::AtomicBool;
+/// use longestlyndonsubseq::longest_lyndon_subsequence_cancellable;
+///
+/// let cancel = AtomicBool::new(false);
+/// let result = longest_lyndon_subsequence_cancellable(b"bccadbaccbcd", &cancel).unwrap();
+/// assert_eq!(result.subsequence(), b"bccbccbcd");
+/// ```
+pub fn longest_lyndon_subsequence_cancellable<T: Ord + Copy>(
+    text: &[T],
+    cancel: &AtomicBool,
+) -> Result<LyndonResult<T>, Cancelled<T>> {
+    let mut longest = Vec::new();
+    let completed = search(
+        text,
+        &T::cmp,
+        leftmost_distinct_symbols_by(text, &T::cmp),
+        &mut CancelOnFlag::new(
+            |stack: &[StackElement]| keep_longest(&mut longest, stack),
+            cancel,
+        ),
+    );
+    let result = LyndonResult::from_stack(text, &longest);
+    if completed {
+        Ok(result)
+    } else {
+        Err(Cancelled { best: result })
+    }
+}
+
+/// computes a longest Lyndon subsequence of `text`, reporting the search state to `callback` every `interval` steps
+///
+/// ```
+/// use longestlyndonsubseq::longest_lyndon_subsequence_with_progress;
+///
+/// let mut reports = 0;
+/// let result = longest_lyndon_subsequence_with_progress(b"bccadbaccbcd", 10, |progress| {
+///     assert!(progress.completed_characters < progress.distinct_characters);
+///     reports += 1;
+/// });
+/// assert_eq!(result.subsequence(), b"bccbccbcd");
+/// assert!(reports > 0);
+/// ```
+pub fn longest_lyndon_subsequence_with_progress<T: Ord + Copy>(
+    text: &[T],
+    interval: u64,
+    callback: impl FnMut(&Progress<T>),
+) -> LyndonResult<T> {
+    let mut longest = Vec::new();
+    let starting_positions = leftmost_distinct_symbols_by(text, &T::cmp);
+    search(
+        text,
+        &T::cmp,
+        starting_positions.iter().copied(),
+        &mut ReportProgress::new(
+            text,
+            starting_positions.len(),
+            interval,
+            |stack: &[StackElement]| keep_longest(&mut longest, stack),
+            callback,
+        ),
+    );
+    LyndonResult::from_stack(text, &longest)
+}
+
+/// computes a longest Lyndon subsequence of `text` over a wide alphabet such as `u16` samples or `u32` token IDs
+///
+/// The symbols are first remapped to their ranks among the distinct symbols of `text`.
+/// If there are at most 256 distinct symbols, the ranks are processed by the byte-specialized solver,
+/// otherwise by the generic solver on the (cheaply comparable) ranks.
+pub fn longest_lyndon_subsequence_wide<T: Ord + Copy>(text: &[T]) -> LyndonResult<T> {
+    let (ranks, alphabet_size) = dense_ranks(text);
+    if alphabet_size <= 1 << u8::BITS {
+        let ranks: Vec<u8> = ranks.iter().map(|&r| r as u8).collect();
+        longest_lyndon_subsequence_bytes(&ranks).map_text(text)
+    } else {
+        longest_lyndon_subsequence(&ranks).map_text(text)
+    }
+}
+
+/// maps each symbol of `text` to its rank among the distinct symbols of `text`;
+/// returns the ranks and the number of distinct symbols
+fn dense_ranks<T: Ord>(text: &[T]) -> (Vec<usize>, usize) {
+    let mut ranks = vec![0; text.len()];
+    let mut alphabet_size = 0;
+    let mut positions: Vec<usize> = (0..text.len()).collect();
+    positions.sort_unstable_by(|&a, &b| text[a].cmp(&text[b]));
+    for (i, &pos) in positions.iter().enumerate() {
+        if i == 0 || text[positions[i - 1]] != text[pos] {
+            alphabet_size += 1;
+        }
+        ranks[pos] = alphabet_size - 1;
+    }
+    (ranks, alphabet_size)
+}
+
+/// computes a longest Lyndon subsequence of the Unicode scalar values of `text`
+///
+/// The positions of the result are codepoint indices, not byte offsets.
+///
+/// ```
+/// use longestlyndonsubseq::longest_lyndon_subsequence_chars;
+///
+/// let result = longest_lyndon_subsequence_chars("äöüaäö");
+/// assert_eq!(result.to_string(), "äöüö");
+/// assert_eq!(result.positions(), [0, 1, 2, 5]);
+/// ```
+pub fn longest_lyndon_subsequence_chars(text: &str) -> LyndonResult<char> {
+    let chars: Vec<char> = text.chars().collect();
+    longest_lyndon_subsequence(&chars)
+}
+
+#[test]
+fn test_lyndon_subsequence() {
+    fn check_subsequence(text: &[u8], result: &[u8]) {
+        assert_eq!(longest_lyndon_subsequence(text).subsequence(), result);
+        assert_eq!(longest_lyndon_subsequence_bytes(text).subsequence(), result);
+        assert_eq!(longest_lyndon_subsequence_len(text), result.len());
+    }
+    check_subsequence(b"bccadbaccbcd", b"bccbccbcd");
+    check_subsequence(b"bccadbaccbc", b"abaccbc");
+    check_subsequence(b"bccadbaccb", b"abaccb");
+    check_subsequence(b"bccadbacc", b"bccdcc");
+    check_subsequence(b"a", b"a");
+    check_subsequence(b"aa", b"a");
+    check_subsequence(b"aaa", b"a");
+    check_subsequence(b"aaab", b"aaab");
+    check_subsequence(b"aaaba", b"aaab");
+}
+
+#[test]
+fn test_batch() {
+    let texts: Vec<Vec<u8>> = (0..50u32)
+        .map(|n| (0..n).map(|i| (i * i % 5) as u8).collect())
+        .rev()
+        .collect();
+    let results = longest_lyndon_subsequences(&texts);
+    assert_eq!(results.len(), texts.len());
+    for (text, result) in texts.iter().zip(&results) {
+        assert_eq!(*result, longest_lyndon_subsequence_bytes(text));
+    }
+}
+
+#[test]
+fn test_generic_alphabet() {
+    // same as b"bccadbaccbcd" with a=1000, b=2000, ...
+    let text: Vec<u32> = b"bccadbaccbcd"
+        .iter()
+        .map(|&c| u32::from(c - b'a' + 1) * 1000)
+        .collect();
+    let result: Vec<u32> = b"bccbccbcd"
+        .iter()
+        .map(|&c| u32::from(c - b'a' + 1) * 1000)
+        .collect();
+    assert_eq!(longest_lyndon_subsequence(&text).subsequence(), result);
+    assert_eq!(
+        longest_lyndon_subsequence(&[3i64, -1, 2]).subsequence(),
+        [-1, 2]
+    );
+    assert_eq!(
+        longest_lyndon_subsequence_bytes(&[255u8, 255]).subsequence(),
+        [255]
+    );
+}
+
+#[test]
+fn test_wide_alphabet() {
+    let text: Vec<u32> = b"bccadbaccbcd"
+        .iter()
+        .map(|&c| u32::MAX - u32::from(b'z' - c))
+        .collect();
+    assert_eq!(
+        longest_lyndon_subsequence_wide(&text).positions(),
+        longest_lyndon_subsequence_bytes(b"bccadbaccbcd").positions()
+    );
+    // more distinct symbols than fit into a byte
+    let text: Vec<u16> = (0..260).rev().chain(0..260).collect();
+    let result = longest_lyndon_subsequence_wide(&text);
+    assert_eq!(result, longest_lyndon_subsequence(&text));
+    assert_eq!(result.len(), 261);
+}
+
+#[test]
+fn test_cancellation() {
+    let text: Vec<u8> = (0..100u32).map(|i| (i * i % 7) as u8).collect();
+    let cancel = AtomicBool::new(true);
+    let cancelled = longest_lyndon_subsequence_cancellable(&text, &cancel).unwrap_err();
+    assert!(!cancelled.best.is_empty());
+    assert!(cancelled.best.len() < longest_lyndon_subsequence_len(&text));
+    cancel.store(false, core::sync::atomic::Ordering::Relaxed);
+    assert_eq!(
+        longest_lyndon_subsequence_cancellable(&text, &cancel).unwrap(),
+        longest_lyndon_subsequence(&text)
+    );
+}
+
+#[test]
+fn test_bounded() {
+    use crate::reference::all_words;
+    // all words of length 7 over {0, 1, 2}
+    for text in all_words(3, 7) {
+        let longest = longest_lyndon_subsequence_len(&text);
+        for max_len in 0..=longest + 1 {
+            let result = longest_lyndon_subsequence_bounded(&text, max_len);
+            assert_eq!(result.len(), max_len.min(longest), "{text:?} {max_len}");
+            assert!(result.is_empty() || is_lyndon(result.subsequence()));
+        }
+    }
+}
+
+#[test]
+fn test_successor_indexes() {
+    use util::{NextOccurrenceIndex, WaveletTree};
+
+    for word in 0..4u32.pow(6) {
+        let text: Vec<u8> = (0..6).map(|i| (word / 4u32.pow(i) % 4) as u8).collect();
+        let expected = longest_lyndon_subsequence(&text);
+        assert_eq!(
+            longest_lyndon_subsequence_with_index(&text, &text[..]),
+            expected
+        );
+        assert_eq!(
+            longest_lyndon_subsequence_with_index(&text, &NextOccurrenceIndex::new(&text)),
+            expected
+        );
+        assert_eq!(
+            longest_lyndon_subsequence_with_index(&text, &WaveletTree::new(&text)),
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_alphabet_order() {
+    // b < c < d < a
+    let rank = |c: &u8| (c + 25) % 26;
+    let bytes: Vec<u8> = b"bccadbaccbcd".iter().map(rank).collect();
+    let result = longest_lyndon_subsequence_by_key(b"bccadbaccbcd", rank);
+    assert_eq!(
+        result.positions(),
+        longest_lyndon_subsequence(&bytes).positions()
+    );
+    assert_eq!(result.subsequence(), b"bccbccbcd");
+    // characters comparing equal are the same character: "AaB" is read as the Lyndon word "aab"
+    let result = longest_lyndon_subsequence_by_key(b"AaBa", u8::to_ascii_lowercase);
+    assert_eq!(result.subsequence(), b"AaB");
+}
+
+#[test]
+fn test_lyndon_result() {
+    let result = longest_lyndon_subsequence_bytes(b"bccadbaccbcd");
+    assert_eq!(result.len(), 9);
+    assert_eq!(result.positions(), [0, 1, 2, 5, 7, 8, 9, 10, 11]);
+    assert_eq!(result.prefix_periods(), [1, 2, 3, 3, 3, 3, 3, 3, 9]);
+    assert_eq!(result.period(), 9);
+    assert_eq!(result.to_vec(), b"bccbccbcd");
+
+    let empty = longest_lyndon_subsequence_bytes(b"");
+    assert!(empty.is_empty());
+    assert_eq!(empty.period(), 0);
+}
@@ -0,0 +1,135 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tokio::task::{self, JoinError};
+
+use crate::{
+    keep_longest, leftmost_distinct_symbols_by, longest_lyndon_subsequence_cancellable, search,
+    CancelOnFlag, LyndonResult, Progress, ReportProgress, StackElement,
+};
+
+/// sets its flag when dropped, i.e., when the future owning it completes or is dropped
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// computes a longest Lyndon subsequence of the byte string `text` without blocking the async executor
+///
+/// The computation runs on tokio's blocking thread pool via [`task::spawn_blocking`],
+/// so this future has to be polled within a tokio runtime.
+/// Dropping the future cancels the computation shortly afterwards.
+/// Fails only if the computation panicked or the runtime shut down in the meantime.
+pub async fn longest_lyndon_subsequence_async<B>(text: B) -> Result<LyndonResult, JoinError>
+where
+    B: AsRef<[u8]> + Send + 'static,
+{
+    let cancel = Arc::new(AtomicBool::new(false));
+    let _guard = CancelOnDrop(cancel.clone());
+    task::spawn_blocking(move || {
+        // a cancelled result is not awaited anymore
+        longest_lyndon_subsequence_cancellable(text.as_ref(), &cancel)
+            .unwrap_or_else(|cancelled| cancelled.best)
+    })
+    .await
+}
+
+/// like [`longest_lyndon_subsequence_async`] for any symbols, reporting the search state to `callback` every `interval` steps
+///
+/// The `callback` is called on the blocking thread, e.g., to send the progress over a channel.
+///
+/// ```
+/// use longestlyndonsubseq::longest_lyndon_subsequence_async_with_progress;
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// let (sender, receiver) = std::sync::mpsc::channel();
+/// let search = longest_lyndon_subsequence_async_with_progress(b"bccadbaccbcd", 10, move |progress| {
+///     sender.send(progress.best_len).unwrap();
+/// });
+/// let result = runtime.block_on(search).unwrap();
+/// assert_eq!(result.subsequence(), b"bccbccbcd");
+/// assert!(receiver.iter().count() > 0);
+/// ```
+pub async fn longest_lyndon_subsequence_async_with_progress<T, B, F>(
+    text: B,
+    interval: u64,
+    mut callback: F,
+) -> Result<LyndonResult<T>, JoinError>
+where
+    T: Ord + Copy + Send + 'static,
+    B: AsRef<[T]> + Send + 'static,
+    F: FnMut(&Progress<T>) + Send + 'static,
+{
+    let cancel = Arc::new(AtomicBool::new(false));
+    let _guard = CancelOnDrop(cancel.clone());
+    task::spawn_blocking(move || {
+        let text = text.as_ref();
+        let mut longest = Vec::new();
+        let starting_positions = leftmost_distinct_symbols_by(text, &T::cmp);
+        search(
+            text,
+            &T::cmp,
+            starting_positions.iter().copied(),
+            &mut CancelOnFlag::new(
+                ReportProgress::new(
+                    text,
+                    starting_positions.len(),
+                    interval,
+                    |stack: &[StackElement]| keep_longest(&mut longest, stack),
+                    &mut callback,
+                ),
+                &cancel,
+            ),
+        );
+        LyndonResult::from_stack(text, &longest)
+    })
+    .await
+}
+
+#[test]
+fn test_async() {
+    use std::future::{poll_fn, Future};
+    use std::sync::mpsc;
+    use std::task::Poll;
+    use std::time::{Duration, Instant};
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let result = runtime
+        .block_on(longest_lyndon_subsequence_async(b"bccadbaccbcd".to_vec()))
+        .unwrap();
+    assert_eq!(result.subsequence(), b"bccbccbcd");
+    // a text whose search takes minutes, which is dropped after it has started
+    let mut state = 1u32;
+    let text: Vec<u8> = (0..4000)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            b'a' + (state >> 16) as u8 % 4
+        })
+        .collect();
+    let (sender, receiver) = mpsc::channel();
+    runtime.block_on(async {
+        let mut search = Box::pin(longest_lyndon_subsequence_async_with_progress(
+            text,
+            1,
+            move |progress| {
+                let _ = sender.send(progress.steps);
+            },
+        ));
+        poll_fn(|context| {
+            assert!(search.as_mut().poll(context).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+        assert!(receiver.recv().is_ok());
+    });
+    // the callback is dropped with the cancelled search
+    let dropped = Instant::now();
+    while receiver.recv().is_ok() {
+        assert!(dropped.elapsed() < Duration::from_secs(30), "not cancelled");
+    }
+}
@@ -0,0 +1,87 @@
+use core::iter;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use alloc::vec::Vec;
+use rayon::prelude::*;
+
+use crate::search::{keep_longest, search, Observer, StackElement};
+use crate::util::leftmost_distinct_symbols_by;
+use crate::LyndonResult;
+
+/// computes a longest Lyndon subsequence of `text` on rayon's thread pool
+///
+/// The subsequences starting with different characters are searched in parallel.
+/// The searches share the length of the longest Lyndon subsequence found so far to skip the branches that cannot exceed it,
+/// but not their other pruning information, so the total work can exceed the one of
+/// [`longest_lyndon_subsequence`](crate::longest_lyndon_subsequence) for texts with few distinct characters.
+/// Among several longest Lyndon subsequences, a different one than there might be returned, also depending on the timing of the searches.
+///
+/// ```
+/// use longestlyndonsubseq::par_longest_lyndon_subsequence;
+///
+/// assert_eq!(par_longest_lyndon_subsequence(b"bccadbaccbcd").subsequence(), b"bccbccbcd");
+/// ```
+pub fn par_longest_lyndon_subsequence<T: Ord + Copy + Sync>(text: &[T]) -> LyndonResult<T> {
+    let best = AtomicUsize::new(0);
+    let longest_per_character: Vec<Vec<StackElement>> = leftmost_distinct_symbols_by(text, &T::cmp)
+        .into_par_iter()
+        .map(|starting_position| {
+            let mut observer = ShareLongest {
+                longest: Vec::new(),
+                best: &best,
+            };
+            search(text, &T::cmp, iter::once(starting_position), &mut observer);
+            observer.longest
+        })
+        .collect();
+    let mut longest = Vec::new();
+    for candidate in &longest_per_character {
+        keep_longest(&mut longest, candidate);
+    }
+    LyndonResult::from_stack(text, &longest)
+}
+
+/// keeps a longest Lyndon subsequence of one search and shares its length with the concurrent searches in `best`
+struct ShareLongest<'a> {
+    longest: Vec<StackElement>,
+    best: &'a AtomicUsize,
+}
+
+impl Observer for ShareLongest<'_> {
+    fn lyndon(&mut self, stack: &[StackElement]) {
+        keep_longest(&mut self.longest, stack);
+        self.best.fetch_max(stack.len(), Ordering::Relaxed);
+    }
+
+    fn known_len(&self) -> usize {
+        self.best.load(Ordering::Relaxed)
+    }
+}
+
+#[test]
+fn test_par_longest_lyndon_subsequence() {
+    use crate::reference::all_words;
+    use crate::{is_lyndon, longest_lyndon_subsequence};
+
+    for text in all_words(3, 8) {
+        let result = par_longest_lyndon_subsequence(&text);
+        assert_eq!(result.len(), longest_lyndon_subsequence(&text).len());
+        assert!(result.is_empty() || is_lyndon(result.subsequence()));
+        assert!(result.positions().windows(2).all(|w| w[0] < w[1]));
+    }
+    // the longest subsequence starting with b is as long as the one found elsewhere, so it is skipped
+    let text = b"bccadbaccbcd";
+    let search_b = |best: &AtomicUsize| {
+        let mut observer = ShareLongest {
+            longest: Vec::new(),
+            best,
+        };
+        search(&text[..], &u8::cmp, iter::once(0), &mut observer);
+        observer.longest.len()
+    };
+    assert!(search_b(&AtomicUsize::new(9)) < 9);
+    assert_eq!(search_b(&AtomicUsize::new(text.len())), 1);
+    let best = AtomicUsize::new(0);
+    assert_eq!(search_b(&best), 9);
+    assert_eq!(best.load(Ordering::Relaxed), 9);
+}
@@ -0,0 +1,122 @@
+//! Combinatorial properties of words over arbitrary ordered alphabets.
+
+use alloc::vec;
+use core::cmp::Ordering;
+
+/// length of the longest Lyndon prefix of `word` if `word` is a prenecklace, i.e., a prefix of a necklace;
+/// this is the period `j - i` of Duval's algorithm after scanning the entire word
+fn prenecklace_period<T: Ord>(word: &[T]) -> Option<usize> {
+    let mut period = 1;
+    for j in 1..word.len() {
+        match word[j - period].cmp(&word[j]) {
+            Ordering::Less => period = j + 1,
+            Ordering::Equal => {}
+            Ordering::Greater => return None,
+        }
+    }
+    Some(period)
+}
+
+/// whether `word` is a Lyndon word, i.e., non-empty and strictly smaller than all its proper suffixes
+///
+/// ```
+/// use longestlyndonsubseq::is_lyndon;
+///
+/// assert!(is_lyndon(b"aab"));
+/// assert!(!is_lyndon(b"abab"));
+/// assert!(!is_lyndon(b"ba"));
+/// ```
+pub fn is_lyndon<T: Ord>(word: &[T]) -> bool {
+    !word.is_empty() && prenecklace_period(word) == Some(word.len())
+}
+
+/// whether `word` is a pre-Lyndon word, i.e., a prefix of a Lyndon word (over an unbounded alphabet)
+///
+/// ```
+/// use longestlyndonsubseq::is_pre_lyndon;
+///
+/// assert!(is_pre_lyndon(b"aba"));
+/// assert!(!is_pre_lyndon(b"baa"));
+/// ```
+pub fn is_pre_lyndon<T: Ord>(word: &[T]) -> bool {
+    word.is_empty() || prenecklace_period(word).is_some()
+}
+
+/// whether `word` is a necklace, i.e., lexicographically not larger than any of its rotations
+///
+/// ```
+/// use longestlyndonsubseq::is_necklace;
+///
+/// assert!(is_necklace(b"abab"));
+/// assert!(!is_necklace(b"aba"));
+/// ```
+pub fn is_necklace<T: Ord>(word: &[T]) -> bool {
+    word.is_empty()
+        || prenecklace_period(word).is_some_and(|period| word.len().is_multiple_of(period))
+}
+
+/// whether `word` is primitive, i.e., non-empty and not a proper power `u^k` with `k >= 2`
+///
+/// ```
+/// use longestlyndonsubseq::is_primitive;
+///
+/// assert!(is_primitive(b"aba"));
+/// assert!(!is_primitive(b"abab"));
+/// ```
+pub fn is_primitive<T: Eq>(word: &[T]) -> bool {
+    if word.is_empty() {
+        return false;
+    }
+    // border array of the Knuth-Morris-Pratt algorithm
+    let mut border = vec![0usize; word.len()];
+    for i in 1..word.len() {
+        let mut b = border[i - 1];
+        while b > 0 && word[i] != word[b] {
+            b = border[b - 1];
+        }
+        if word[i] == word[b] {
+            b += 1;
+        }
+        border[i] = b;
+    }
+    let period = word.len() - border[word.len() - 1];
+    period == word.len() || !word.len().is_multiple_of(period)
+}
+
+#[test]
+fn test_predicates() {
+    use crate::reference::all_words;
+    use alloc::vec::Vec;
+
+    fn rotations(word: &[u8]) -> Vec<Vec<u8>> {
+        (1..word.len())
+            .map(|i| [&word[i..], &word[..i]].concat())
+            .collect()
+    }
+    fn brute_primitive(word: &[u8]) -> bool {
+        !word.is_empty() && rotations(word).iter().all(|r| r != word)
+    }
+    fn brute_necklace(word: &[u8]) -> bool {
+        rotations(word).iter().all(|r| word <= &r[..])
+    }
+
+    // compare with the definitions on all words of length at most 7 over {0, 1, 2}
+    for len in 0..=7 {
+        for word in all_words(3, len) {
+            // appending a character larger than all others yields a Lyndon word iff the word is pre-Lyndon
+            let extended = [&word[..], &[3]].concat();
+            assert_eq!(is_primitive(&word), brute_primitive(&word), "{word:?}");
+            assert_eq!(is_necklace(&word), brute_necklace(&word), "{word:?}");
+            assert_eq!(
+                is_lyndon(&word),
+                brute_primitive(&word) && brute_necklace(&word),
+                "{word:?}"
+            );
+            assert_eq!(
+                is_pre_lyndon(&word),
+                brute_primitive(&extended) && brute_necklace(&extended),
+                "{word:?}"
+            );
+        }
+    }
+}
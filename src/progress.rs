@@ -0,0 +1,97 @@
+use core::ops::ControlFlow;
+
+use crate::search::{Observer, SearchEvent, StackElement};
+
+/// snapshot of the search state, see [`longest_lyndon_subsequence_with_progress`](crate::longest_lyndon_subsequence_with_progress)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress<T = u8> {
+    /// the first character of the subsequences currently searched
+    pub starting_character: T,
+    /// number of distinct characters whose search has been completed
+    pub completed_characters: usize,
+    /// number of distinct characters of the text, i.e., the total number of searches
+    pub distinct_characters: usize,
+    /// length of the subsequence currently visited
+    pub depth: usize,
+    /// length of the longest Lyndon subsequence found so far
+    pub best_len: usize,
+    /// number of steps of the traversal so far
+    pub steps: u64,
+}
+
+/// reports the search state of `inner` to `callback` every `interval` steps
+pub(crate) struct ReportProgress<'a, T, O, F> {
+    text: &'a [T],
+    inner: O,
+    callback: F,
+    interval: u64,
+    distinct_characters: usize,
+    started_characters: usize,
+    best_len: usize,
+    steps: u64,
+}
+
+impl<'a, T, O, F> ReportProgress<'a, T, O, F> {
+    pub(crate) fn new(
+        text: &'a [T],
+        distinct_characters: usize,
+        interval: u64,
+        inner: O,
+        callback: F,
+    ) -> Self {
+        ReportProgress {
+            text,
+            inner,
+            callback,
+            interval: interval.max(1),
+            distinct_characters,
+            started_characters: 0,
+            best_len: 0,
+            steps: 0,
+        }
+    }
+}
+
+impl<T: Copy, O: Observer, F: FnMut(&Progress<T>)> Observer for ReportProgress<'_, T, O, F> {
+    fn lyndon(&mut self, stack: &[StackElement]) {
+        self.best_len = self.best_len.max(stack.len());
+        self.inner.lyndon(stack)
+    }
+
+    fn start(&mut self, starting_position: usize) {
+        self.started_characters += 1;
+        self.inner.start(starting_position)
+    }
+
+    fn event(&mut self, event: SearchEvent) {
+        self.inner.event(event)
+    }
+
+    fn step(&mut self, stack: &[StackElement]) -> ControlFlow<()> {
+        self.inner.step(stack)?;
+        self.steps += 1;
+        if self.steps.is_multiple_of(self.interval) {
+            (self.callback)(&Progress {
+                starting_character: self.text[stack[0].text_pos],
+                completed_characters: self.started_characters - 1,
+                distinct_characters: self.distinct_characters,
+                depth: stack.len(),
+                best_len: self.best_len,
+                steps: self.steps,
+            });
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn max_len(&self) -> usize {
+        self.inner.max_len()
+    }
+
+    fn prune(&self) -> bool {
+        self.inner.prune()
+    }
+
+    fn known_len(&self) -> usize {
+        self.inner.known_len()
+    }
+}
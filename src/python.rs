@@ -0,0 +1,28 @@
+//! Python bindings, built as the extension module `longestlyndon` with `maturin` (see `pyproject.toml`)
+
+use pyo3::prelude::*;
+
+#[pymodule]
+mod longestlyndon {
+    use alloc::vec::Vec;
+
+    use pyo3::buffer::PyBuffer;
+    use pyo3::prelude::*;
+    use pyo3::types::PyBytes;
+
+    /// Computes a longest Lyndon subsequence of `text`, which can be any object supporting
+    /// the buffer protocol with byte items (bytes, bytearray, memoryview, ...).
+    /// Returns the subsequence and its text positions.
+    #[pyfunction]
+    fn longest_lyndon_subsequence<'py>(
+        py: Python<'py>,
+        text: &Bound<'py, PyAny>,
+    ) -> PyResult<(Bound<'py, PyBytes>, Vec<usize>)> {
+        let text = PyBuffer::<u8>::get(text)?.to_vec(py)?;
+        let result = py.detach(|| crate::longest_lyndon_subsequence_bytes(&text));
+        Ok((
+            PyBytes::new(py, result.subsequence()),
+            result.positions().to_vec(),
+        ))
+    }
+}
@@ -0,0 +1,83 @@
+use core::ops::Bound;
+
+use alloc::vec::Vec;
+
+use crate::search::{keep_longest, search_with_successor, Bounded, SearchBuffers, StackElement};
+use crate::util::NextOccurrenceIndex;
+use crate::LyndonResult;
+
+/// preprocessed text answering longest Lyndon subsequence queries on its substrings
+///
+/// Building takes `O(nσ)` time and space for a text of length `n` with `σ` distinct characters.
+/// Each query then answers the successor queries of the search in `O(σ)` time
+/// instead of scanning the remaining substring.
+///
+/// ```
+/// use longestlyndonsubseq::{longest_lyndon_subsequence, RangeQuery};
+///
+/// let text = b"bccadbaccbcd";
+/// let index = RangeQuery::new(text);
+/// assert_eq!(index.query(3, 11).subsequence(), b"abaccbc");
+/// assert_eq!(index.query(3, 11), longest_lyndon_subsequence(&text[3..11]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RangeQuery<'a, T> {
+    text: &'a [T],
+    index: NextOccurrenceIndex<T>,
+}
+
+impl<'a, T: Ord + Copy> RangeQuery<'a, T> {
+    /// preprocesses `text`
+    pub fn new(text: &'a [T]) -> Self {
+        RangeQuery {
+            text,
+            index: NextOccurrenceIndex::new(text),
+        }
+    }
+
+    /// the preprocessed text
+    pub fn text(&self) -> &'a [T] {
+        self.text
+    }
+
+    /// computes a longest Lyndon subsequence of `text[i..j]`, with positions relative to `i`
+    ///
+    /// The result is the same as the one of [`longest_lyndon_subsequence`](crate::longest_lyndon_subsequence) on `text[i..j]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i > j` or `j` exceeds the length of the text.
+    pub fn query(&self, i: usize, j: usize) -> LyndonResult<T> {
+        let range = &self.text[i..j];
+        let mut longest = Vec::new();
+        search_with_successor(
+            &mut SearchBuffers::default(),
+            self.text,
+            &T::cmp,
+            |start, bound: Bound<&T>| self.index.successor_in(start..j, bound),
+            self.index.leftmost_distinct_in(i..j),
+            &mut Bounded {
+                inner: |stack: &[StackElement]| keep_longest(&mut longest, stack),
+                max_len: range.len(),
+            },
+        );
+        for element in &mut longest {
+            element.text_pos -= i;
+        }
+        LyndonResult::from_stack(range, &longest)
+    }
+}
+
+#[test]
+fn test_range_query() {
+    let text: Vec<u8> = (0..40u32).map(|i| (i * i % 11 % 4) as u8).collect();
+    let index = RangeQuery::new(&text);
+    for i in 0..=text.len() {
+        for j in i..=text.len() {
+            assert_eq!(
+                index.query(i, j),
+                crate::longest_lyndon_subsequence(&text[i..j])
+            );
+        }
+    }
+}
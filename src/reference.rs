@@ -0,0 +1,92 @@
+//! Exhaustive reference implementations for cross-checking the optimized algorithms on small inputs.
+
+use alloc::vec::Vec;
+
+use crate::search::StackElement;
+use crate::{is_lyndon, LyndonResult};
+
+/// computes the lexicographically smallest longest Lyndon subsequence of `text` by checking all its `2^n` subsequences
+///
+/// The subsequence is reported at its leftmost occurrence in `text`.
+/// Only meant for short texts, the running time is `O(2^n n)` for a text of length `n`.
+///
+/// ```
+/// use longestlyndonsubseq::longest_lyndon_subsequence;
+/// use longestlyndonsubseq::reference::brute_force_longest_lyndon_subsequence;
+///
+/// let text = b"bccadbaccbcd";
+/// let expected = brute_force_longest_lyndon_subsequence(text);
+/// assert_eq!(longest_lyndon_subsequence(text).len(), expected.len());
+/// ```
+///
+/// # Panics
+///
+/// Panics if `text` has 64 or more characters.
+pub fn brute_force_longest_lyndon_subsequence<T: Ord + Copy>(text: &[T]) -> LyndonResult<T> {
+    assert!(
+        text.len() < 64,
+        "the brute-force solver only supports texts shorter than 64 characters"
+    );
+    let mut best: Vec<T> = Vec::new();
+    for mask in 1..1u64 << text.len() {
+        let word: Vec<T> = (0..text.len())
+            .filter(|i| mask >> i & 1 == 1)
+            .map(|i| text[i])
+            .collect();
+        let better = word.len() > best.len() || (word.len() == best.len() && word < best);
+        if better && is_lyndon(&word) {
+            best = word;
+        }
+    }
+    let mut positions = Vec::with_capacity(best.len());
+    let mut next = 0;
+    for c in &best {
+        let pos = next + text[next..].iter().position(|x| x == c).unwrap();
+        positions.push(pos);
+        next = pos + 1;
+    }
+    let stack: Vec<StackElement> = positions
+        .iter()
+        .enumerate()
+        .map(|(i, &text_pos)| StackElement {
+            text_pos,
+            period: smallest_period(&best[..=i]),
+        })
+        .collect();
+    LyndonResult::from_stack(text, &stack)
+}
+
+/// all words of length `len` over the alphabet `0..sigma`, for exhaustive tests
+///
+/// ```
+/// use longestlyndonsubseq::reference::all_words;
+///
+/// assert_eq!(all_words(2, 2).collect::<Vec<_>>(), [[0, 0], [1, 0], [0, 1], [1, 1]]);
+/// ```
+pub fn all_words(sigma: u8, len: usize) -> impl Iterator<Item = Vec<u8>> {
+    let sigma = usize::from(sigma);
+    (0..sigma.pow(len as u32)).map(move |code| {
+        (0..len as u32)
+            .map(|i| (code / sigma.pow(i) % sigma) as u8)
+            .collect()
+    })
+}
+
+/// smallest period of the non-empty `word`
+fn smallest_period<T: Eq>(word: &[T]) -> usize {
+    (1..word.len())
+        .find(|&p| word[p..].iter().zip(word).all(|(a, b)| a == b))
+        .unwrap_or(word.len())
+}
+
+#[test]
+fn test_brute_force() {
+    use crate::{longest_lyndon_subsequence, Solver};
+
+    for text in all_words(3, 8) {
+        let expected = brute_force_longest_lyndon_subsequence(&text);
+        assert_eq!(longest_lyndon_subsequence(&text).len(), expected.len());
+        // the unpruned search visits the subsequences in lexicographic order
+        assert_eq!(Solver::new().prune(false).solve(&text), expected);
+    }
+}
@@ -0,0 +1,211 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::StackElement;
+
+/// a longest Lyndon subsequence, as returned by [`longest_lyndon_subsequence`](crate::longest_lyndon_subsequence)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LyndonResult<T = u8> {
+    positions: Vec<usize>,
+    #[cfg_attr(feature = "serde", serde(rename = "prefix_periods"))]
+    periods: Vec<usize>,
+    #[cfg_attr(feature = "serde", serde(rename = "subsequence"))]
+    symbols: Vec<T>,
+}
+
+impl<T: Copy> LyndonResult<T> {
+    /// collects the characters of `text` at the positions stored in `stack`
+    pub(crate) fn from_stack(text: &[T], stack: &[StackElement]) -> Self {
+        LyndonResult {
+            positions: stack.iter().map(|el| el.text_pos).collect(),
+            periods: stack.iter().map(|el| el.period).collect(),
+            symbols: stack.iter().map(|el| text[el.text_pos]).collect(),
+        }
+    }
+
+    /// the same subsequence, but with its characters taken from `text` (e.g., the original text of a remapped text)
+    pub(crate) fn map_text<U: Copy>(self, text: &[U]) -> LyndonResult<U> {
+        LyndonResult {
+            symbols: self.positions.iter().map(|&pos| text[pos]).collect(),
+            positions: self.positions,
+            periods: self.periods,
+        }
+    }
+
+    /// length of the Lyndon subsequence
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// whether the subsequence is empty, which only happens for an empty text
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// the strictly increasing text positions the subsequence is composed of
+    pub fn positions(&self) -> &[usize] {
+        &self.positions
+    }
+
+    /// the same subsequence at the positions `f(pos)`, e.g., in the text from which the searched text was filtered; `f` has to be strictly increasing
+    pub fn map_positions(mut self, f: impl FnMut(usize) -> usize) -> Self {
+        self.positions = self.positions.into_iter().map(f).collect();
+        self
+    }
+
+    /// smallest period of the subsequence; equals [`len`](Self::len) since Lyndon words are border-free
+    pub fn period(&self) -> usize {
+        self.periods.last().copied().unwrap_or(0)
+    }
+
+    /// smallest periods of all prefixes of the subsequence
+    pub fn prefix_periods(&self) -> &[usize] {
+        &self.periods
+    }
+
+    /// how the search composed the subsequence, see [`LyndonStructure`]
+    ///
+    /// ```
+    /// use longestlyndonsubseq::{longest_lyndon_subsequence, LyndonStructure};
+    ///
+    /// // bccbccbc = (bcc)^2 bc, followed by d
+    /// let result = longest_lyndon_subsequence(b"bccadbaccbcd");
+    /// let structure = LyndonStructure { root_period: 3, exponent: 2, immature_start: 6 };
+    /// assert_eq!(result.structure(), structure);
+    /// assert!(!structure.is_power_prefix(result.len()));
+    /// ```
+    pub fn structure(&self) -> LyndonStructure {
+        let Some(prefix_len) = self.len().checked_sub(1).filter(|&len| len > 0) else {
+            return LyndonStructure::default();
+        };
+        let root_period = self.periods[prefix_len - 1];
+        let exponent = prefix_len / root_period;
+        LyndonStructure {
+            root_period,
+            exponent,
+            immature_start: exponent * root_period,
+        }
+    }
+
+    /// the characters of the subsequence
+    pub fn subsequence(&self) -> &[T] {
+        &self.symbols
+    }
+
+    /// materializes the subsequence as an owned vector
+    pub fn to_vec(&self) -> Vec<T> {
+        self.symbols.clone()
+    }
+
+    /// consumes the result, returning the characters of the subsequence
+    pub fn into_subsequence(self) -> Vec<T> {
+        self.symbols
+    }
+}
+
+/// the decomposition of a Lyndon subsequence without its last character into `u^exponent u'`,
+/// where `u` is a Lyndon word of length `root_period` and the immature part `u'` is a proper prefix of `u`;
+/// the last character is larger than the character following `u'` in `u`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LyndonStructure {
+    /// the smallest period of the subsequence without its last character, 0 for at most one character
+    pub root_period: usize,
+    /// how often `u` is repeated
+    pub exponent: usize,
+    /// the position of `u'` in the subsequence
+    pub immature_start: usize,
+}
+
+impl LyndonStructure {
+    /// whether the subsequence of length `len` without its last character is a power of the Lyndon word `u`, i.e., `u'` is empty
+    pub fn is_power_prefix(&self, len: usize) -> bool {
+        self.immature_start + 1 == len
+    }
+}
+
+impl LyndonResult<u8> {
+    /// the bytes of the subsequence
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.symbols
+    }
+
+    /// the subsequence as a string, with invalid UTF-8 sequences replaced by `U+FFFD`
+    pub fn to_string_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.symbols)
+    }
+
+    /// writes the raw bytes of the subsequence to `writer`
+    #[cfg(feature = "std")]
+    pub fn write_to(&self, mut writer: impl std::io::Write) -> std::io::Result<()> {
+        writer.write_all(&self.symbols)
+    }
+}
+
+/// writes the subsequence as a string, with invalid UTF-8 sequences replaced by `U+FFFD`
+impl fmt::Display for LyndonResult<u8> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in self.symbols.utf8_chunks() {
+            f.write_str(chunk.valid())?;
+            if !chunk.invalid().is_empty() {
+                fmt::Write::write_char(f, char::REPLACEMENT_CHARACTER)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// writes the subsequence as a string
+impl fmt::Display for LyndonResult<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.symbols
+            .iter()
+            .try_for_each(|c| fmt::Write::write_char(f, *c))
+    }
+}
+
+#[test]
+fn test_conversions() {
+    use alloc::string::ToString;
+
+    let result = crate::longest_lyndon_subsequence_bytes(b"bccadbaccbcd");
+    assert_eq!(result.as_bytes(), b"bccbccbcd");
+    assert_eq!(result.to_string(), "bccbccbcd");
+    let shifted = result.clone().map_positions(|pos| pos + 1);
+    assert_eq!(shifted.positions()[0], result.positions()[0] + 1);
+    assert_eq!(shifted.subsequence(), result.subsequence());
+    assert_eq!(result.to_string_lossy(), "bccbccbcd");
+    let structure = crate::longest_lyndon_subsequence_bytes(b"aaab").structure();
+    assert_eq!((structure.root_period, structure.exponent), (1, 3));
+    assert!(structure.is_power_prefix(4));
+    assert_eq!(
+        crate::longest_lyndon_subsequence_bytes(b"a").structure(),
+        LyndonStructure::default()
+    );
+    #[cfg(feature = "std")]
+    {
+        let mut out = Vec::new();
+        result.write_to(&mut out).unwrap();
+        assert_eq!(out, b"bccbccbcd");
+    }
+
+    // 0xff is never valid UTF-8
+    let result = crate::longest_lyndon_subsequence_bytes(b"a\xffb\xff");
+    assert_eq!(result.as_bytes(), b"a\xffb\xff");
+    assert_eq!(result.to_string(), "a\u{fffd}b\u{fffd}");
+    assert_eq!(result.to_string_lossy(), result.to_string());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    let result = crate::longest_lyndon_subsequence_bytes(b"aaab");
+    let json = serde_json::to_string(&result).unwrap();
+    assert_eq!(
+        json,
+        r#"{"positions":[0,1,2,3],"prefix_periods":[1,1,1,4],"subsequence":[97,97,97,98]}"#
+    );
+    assert_eq!(serde_json::from_str::<LyndonResult>(&json).unwrap(), result);
+}
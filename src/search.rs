@@ -0,0 +1,348 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::ops::{Bound, ControlFlow};
+use core::sync::atomic::{self, AtomicBool};
+
+use crate::util::successor_element_by;
+
+/// an element of the search stack, i.e., a character of the currently visited Lyndon subsequence
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct StackElement {
+    /// position of the character in the text
+    pub(crate) text_pos: usize,
+    /// smallest period of the subsequence ending with this character
+    pub(crate) period: usize,
+}
+
+/// a step of the traversal of the trie of subsequences that are prefixes of Lyndon words,
+/// see [`longest_lyndon_subsequence_with_events`](crate::longest_lyndon_subsequence_with_events)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SearchEvent {
+    /// the subsequence is extended by the character at `text_pos` to `depth` characters with the smallest period `period`;
+    /// it is a Lyndon subsequence if `period == depth`
+    Push {
+        text_pos: usize,
+        depth: usize,
+        period: usize,
+    },
+    /// the last character, at `text_pos`, is removed from the subsequence of `depth` characters
+    Pop { text_pos: usize, depth: usize },
+    /// the leftmost position from `start` on is searched whose character is larger than the one at `bound`,
+    /// or at least as large if `inclusive`, and `found`
+    Successor {
+        start: usize,
+        bound: usize,
+        inclusive: bool,
+        found: Option<usize>,
+    },
+    /// a Lyndon subsequence of `depth` characters ending at `text_pos` is recorded,
+    /// which prunes the extensions to `depth` characters further right
+    Larray { depth: usize, text_pos: usize },
+    /// the extension by the character at `text_pos` to `depth` characters is skipped,
+    /// since a Lyndon subsequence of length `depth` ending further left is already known,
+    /// or since it cannot get longer than a Lyndon subsequence found elsewhere
+    Prune { text_pos: usize, depth: usize },
+}
+
+/// hooks into the traversal of [`search`]
+pub(crate) trait Observer {
+    /// called whenever the stack spells a Lyndon subsequence
+    fn lyndon(&mut self, stack: &[StackElement]);
+
+    /// called when the search starts at `starting_position`, the leftmost occurrence of the next distinct character
+    fn start(&mut self, _starting_position: usize) {}
+
+    /// called before each step of the traversal; breaking aborts the search
+    fn step(&mut self, _stack: &[StackElement]) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// the search does not grow subsequences beyond this length
+    fn max_len(&self) -> usize {
+        usize::MAX
+    }
+
+    /// whether to skip branches that cannot lead to a longer Lyndon subsequence
+    fn prune(&self) -> bool {
+        true
+    }
+
+    /// the length of a Lyndon subsequence found elsewhere, e.g., by a concurrent search;
+    /// the search skips the extensions that have too few characters to their right to get longer
+    fn known_len(&self) -> usize {
+        0
+    }
+
+    /// called for every change of the stack and every pruned branch
+    fn event(&mut self, _event: SearchEvent) {}
+}
+
+impl<F: FnMut(&[StackElement])> Observer for F {
+    fn lyndon(&mut self, stack: &[StackElement]) {
+        self(stack)
+    }
+}
+
+/// restricts the search of `inner` to subsequences of length at most `max_len`
+pub(crate) struct Bounded<O> {
+    pub(crate) inner: O,
+    pub(crate) max_len: usize,
+}
+
+impl<O: Observer> Observer for Bounded<O> {
+    fn lyndon(&mut self, stack: &[StackElement]) {
+        self.inner.lyndon(stack)
+    }
+
+    fn start(&mut self, starting_position: usize) {
+        self.inner.start(starting_position)
+    }
+
+    fn step(&mut self, stack: &[StackElement]) -> ControlFlow<()> {
+        self.inner.step(stack)
+    }
+
+    fn max_len(&self) -> usize {
+        self.max_len.min(self.inner.max_len())
+    }
+
+    fn prune(&self) -> bool {
+        self.inner.prune()
+    }
+
+    fn known_len(&self) -> usize {
+        self.inner.known_len()
+    }
+
+    fn event(&mut self, event: SearchEvent) {
+        self.inner.event(event)
+    }
+}
+
+/// keeps a longest Lyndon subsequence in `longest` while passing the events of the search to `callback`
+pub(crate) struct ReportEvents<'a, F> {
+    pub(crate) longest: &'a mut Vec<StackElement>,
+    pub(crate) callback: F,
+}
+
+impl<F: FnMut(SearchEvent)> Observer for ReportEvents<'_, F> {
+    fn lyndon(&mut self, stack: &[StackElement]) {
+        keep_longest(self.longest, stack)
+    }
+
+    fn event(&mut self, event: SearchEvent) {
+        (self.callback)(event)
+    }
+}
+
+/// number of steps between two checks of a cancellation flag
+const CANCELLATION_CHECK_INTERVAL: usize = 1 << 10;
+
+/// aborts the search of `inner` once `flag` is set
+pub(crate) struct CancelOnFlag<'a, O> {
+    pub(crate) inner: O,
+    pub(crate) flag: &'a AtomicBool,
+    pub(crate) steps: usize,
+}
+
+impl<'a, O> CancelOnFlag<'a, O> {
+    pub(crate) fn new(inner: O, flag: &'a AtomicBool) -> Self {
+        CancelOnFlag {
+            inner,
+            flag,
+            steps: 0,
+        }
+    }
+}
+
+impl<O: Observer> Observer for CancelOnFlag<'_, O> {
+    fn lyndon(&mut self, stack: &[StackElement]) {
+        self.inner.lyndon(stack)
+    }
+
+    fn start(&mut self, starting_position: usize) {
+        self.inner.start(starting_position)
+    }
+
+    fn step(&mut self, stack: &[StackElement]) -> ControlFlow<()> {
+        self.inner.step(stack)?;
+        self.steps += 1;
+        if self.steps.is_multiple_of(CANCELLATION_CHECK_INTERVAL)
+            && self.flag.load(atomic::Ordering::Relaxed)
+        {
+            return ControlFlow::Break(());
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn max_len(&self) -> usize {
+        self.inner.max_len()
+    }
+
+    fn prune(&self) -> bool {
+        self.inner.prune()
+    }
+
+    fn known_len(&self) -> usize {
+        self.inner.known_len()
+    }
+
+    fn event(&mut self, event: SearchEvent) {
+        self.inner.event(event)
+    }
+}
+
+/// snapshots `stack` into `longest` if it is longer
+pub(crate) fn keep_longest(longest: &mut Vec<StackElement>, stack: &[StackElement]) {
+    if longest.len() < stack.len() {
+        longest.clear();
+        longest.extend_from_slice(stack);
+    }
+}
+
+/// traverses the Lyndon subsequence trie, starting with the characters at `starting_positions`
+/// (the leftmost occurrences of each distinct character in ascending order)
+///
+/// The `observer` is notified about every Lyndon subsequence and can abort the search,
+/// in which case `false` is returned.
+pub(crate) fn search<T: Copy>(
+    text: &[T],
+    cmp: &impl Fn(&T, &T) -> Ordering,
+    starting_positions: impl IntoIterator<Item = usize>,
+    observer: &mut impl Observer,
+) -> bool {
+    search_with_successor(
+        &mut SearchBuffers::default(),
+        text,
+        cmp,
+        |start, bound| successor_element_by(text, cmp, start, bound),
+        starting_positions,
+        observer,
+    )
+}
+
+/// allocations of [`search`] that can be reused across searches
+#[derive(Debug, Default)]
+pub(crate) struct SearchBuffers {
+    larray: Vec<usize>,
+    stack: Vec<StackElement>,
+}
+
+/// [`search`] answering the successor queries of the traversal with `successor`,
+/// which has to behave like [`successor_element_by`], and working in `buffers`
+pub(crate) fn search_with_successor<T: Copy>(
+    buffers: &mut SearchBuffers,
+    text: &[T],
+    cmp: &impl Fn(&T, &T) -> Ordering,
+    successor: impl Fn(usize, Bound<&T>) -> Option<usize>,
+    starting_positions: impl IntoIterator<Item = usize>,
+    observer: &mut impl Observer,
+) -> bool {
+    let max_len = observer.max_len().min(text.len());
+    if max_len == 0 {
+        return true;
+    }
+    let prune = observer.prune();
+    let SearchBuffers { larray, stack } = buffers;
+    larray.clear();
+    larray.resize(max_len + 1, usize::MAX);
+    stack.clear();
+
+    for starting_position in starting_positions {
+        stack.push(StackElement {
+            text_pos: starting_position,
+            period: 1,
+        });
+        observer.start(starting_position);
+        observer.event(SearchEvent::Push {
+            text_pos: starting_position,
+            depth: 1,
+            period: 1,
+        });
+        observer.lyndon(stack);
+        //@ set after an upward move: the next child must have a larger edge label (the character at this position)
+        let mut lastchildedge: Option<usize> = None;
+        while !stack.is_empty() {
+            if observer.step(stack).is_break() {
+                return false;
+            }
+            let top = stack.last().unwrap();
+            let immature_character = text[stack[stack.len() - top.period].text_pos];
+            let next = match lastchildedge {
+                _ if stack.len() >= max_len => None,
+                Some(edge) => {
+                    let next = successor(top.text_pos + 1, Bound::Excluded(&text[edge]));
+                    observer.event(SearchEvent::Successor {
+                        start: top.text_pos + 1,
+                        bound: edge,
+                        inclusive: false,
+                        found: next,
+                    });
+                    next
+                }
+                None => {
+                    let next = successor(top.text_pos + 1, Bound::Included(&immature_character));
+                    observer.event(SearchEvent::Successor {
+                        start: top.text_pos + 1,
+                        bound: stack[stack.len() - top.period].text_pos,
+                        inclusive: true,
+                        found: next,
+                    });
+                    next
+                }
+            };
+            match next {
+                None => {
+                    lastchildedge = Some(top.text_pos);
+                    observer.event(SearchEvent::Pop {
+                        text_pos: top.text_pos,
+                        depth: stack.len(),
+                    });
+                    stack.pop();
+                }
+                Some(i) => {
+                    assert!(top.text_pos < i);
+                    assert!(cmp(&immature_character, &text[i]) != Ordering::Greater);
+                    let subsequence_length = stack.len() + 1;
+                    // the extension ends with at most all characters right of `i`
+                    let short = subsequence_length + (text.len() - 1 - i) <= observer.known_len();
+                    if short || prune && larray[subsequence_length] < i {
+                        lastchildedge = Some(i);
+                        observer.event(SearchEvent::Prune {
+                            text_pos: i,
+                            depth: subsequence_length,
+                        });
+                    } else {
+                        let new_period = if cmp(&immature_character, &text[i]) == Ordering::Equal {
+                            top.period
+                        } else {
+                            subsequence_length
+                        };
+
+                        stack.push(StackElement {
+                            text_pos: i,
+                            period: new_period,
+                        });
+                        observer.event(SearchEvent::Push {
+                            text_pos: i,
+                            depth: subsequence_length,
+                            period: new_period,
+                        });
+                        if new_period == subsequence_length {
+                            //@ only update larray if we have a Lyndon subsequence
+                            larray[subsequence_length] = i;
+                            observer.event(SearchEvent::Larray {
+                                depth: subsequence_length,
+                                text_pos: i,
+                            });
+                            observer.lyndon(stack);
+                        }
+                        lastchildedge = None;
+                    }
+                }
+            }
+        }
+    }
+    true
+}
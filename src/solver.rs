@@ -0,0 +1,250 @@
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+use core::ops::ControlFlow;
+
+use alloc::vec::Vec;
+
+use crate::search::{
+    keep_longest, search, search_with_successor, Observer, SearchBuffers, StackElement,
+};
+use crate::util::{leftmost_distinct_characters, leftmost_distinct_symbols_by, successor_element};
+use crate::LyndonResult;
+
+/// which of several longest Lyndon subsequences is returned
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// the first one visited, i.e., the lexicographically smallest one if pruning is disabled
+    #[default]
+    First,
+    /// the last one visited, i.e., the lexicographically largest one if pruning is disabled
+    Last,
+}
+
+/// counters collected by [`Solver::solve_with_stats`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SearchStats {
+    /// number of steps of the traversal
+    pub steps: u64,
+    /// number of Lyndon subsequences visited
+    pub lyndon_subsequences: u64,
+}
+
+/// configurable computation of a longest Lyndon subsequence
+///
+/// ```
+/// use longestlyndonsubseq::{Solver, TieBreak};
+///
+/// let result = Solver::new().max_len(4).solve(b"bccadbaccbcd");
+/// assert_eq!(result.subsequence(), b"aabc");
+///
+/// let result = Solver::new()
+///     .order(|a: &u8, b: &u8| b.cmp(a))
+///     .tie_break(TieBreak::Last)
+///     .prune(false)
+///     .solve(b"abcab");
+/// assert_eq!(result.subsequence(), b"cab");
+/// ```
+#[derive(Clone, Copy)]
+pub struct Solver<T, C = fn(&T, &T) -> Ordering> {
+    cmp: C,
+    max_len: usize,
+    prune: bool,
+    tie_break: TieBreak,
+    symbol: PhantomData<fn(&T)>,
+}
+
+impl<T: Ord> Solver<T> {
+    /// creates a solver with the natural order of `T` and all other options at their defaults
+    pub fn new() -> Self {
+        Solver {
+            cmp: T::cmp,
+            max_len: usize::MAX,
+            prune: true,
+            tie_break: TieBreak::First,
+            symbol: PhantomData,
+        }
+    }
+}
+
+impl<T: Ord> Default for Solver<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy, C: Fn(&T, &T) -> Ordering> Solver<T, C> {
+    /// sets the alphabet order; characters comparing [`Ordering::Equal`] are treated as the same character
+    pub fn order<D: Fn(&T, &T) -> Ordering>(self, cmp: D) -> Solver<T, D> {
+        Solver {
+            cmp,
+            max_len: self.max_len,
+            prune: self.prune,
+            tie_break: self.tie_break,
+            symbol: PhantomData,
+        }
+    }
+
+    /// only considers Lyndon subsequences of length at most `max_len`
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// enables or disables pruning (enabled by default); disabling it visits every Lyndon subsequence
+    pub fn prune(mut self, prune: bool) -> Self {
+        self.prune = prune;
+        self
+    }
+
+    /// sets which longest Lyndon subsequence is returned
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /// computes a longest Lyndon subsequence of `text`
+    pub fn solve(&self, text: &[T]) -> LyndonResult<T> {
+        self.solve_with_stats(text).0
+    }
+
+    /// computes a longest Lyndon subsequence of `text` and counters about the search
+    pub fn solve_with_stats(&self, text: &[T]) -> (LyndonResult<T>, SearchStats) {
+        let mut observer = SolverObserver {
+            solver: self,
+            longest: Vec::new(),
+            stats: SearchStats::default(),
+        };
+        search(
+            text,
+            &self.cmp,
+            leftmost_distinct_symbols_by(text, &self.cmp),
+            &mut observer,
+        );
+        (
+            LyndonResult::from_stack(text, &observer.longest),
+            observer.stats,
+        )
+    }
+}
+
+/// computes longest Lyndon subsequences of byte strings one after another, reusing the working memory of the search
+///
+/// ```
+/// use longestlyndonsubseq::BatchSolver;
+///
+/// let mut solver = BatchSolver::new();
+/// assert_eq!(solver.solve(b"bccadbaccbcd").subsequence(), b"bccbccbcd");
+/// assert_eq!(solver.solve(b"aaaba").subsequence(), b"aaab");
+/// ```
+#[derive(Debug, Default)]
+pub struct BatchSolver {
+    buffers: SearchBuffers,
+    longest: Vec<StackElement>,
+}
+
+impl BatchSolver {
+    /// creates a solver without any allocated memory
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// same as [`longest_lyndon_subsequence_bytes`](crate::longest_lyndon_subsequence_bytes)
+    pub fn solve(&mut self, text: &[u8]) -> LyndonResult {
+        let longest = &mut self.longest;
+        longest.clear();
+        search_with_successor(
+            &mut self.buffers,
+            text,
+            &u8::cmp,
+            |start, bound| successor_element(text, start, bound),
+            leftmost_distinct_characters(text),
+            &mut |stack: &[StackElement]| keep_longest(longest, stack),
+        );
+        LyndonResult::from_stack(text, longest)
+    }
+
+    /// same as [`longest_lyndon_subsequence_len`](crate::longest_lyndon_subsequence_len),
+    /// but allocating only if `text` is longer than the ones before
+    ///
+    /// ```
+    /// use longestlyndonsubseq::BatchSolver;
+    ///
+    /// let mut solver = BatchSolver::new();
+    /// assert_eq!(solver.solve_len(b"bccadbaccbcd"), 9);
+    /// assert_eq!(solver.solve_len(b"aaaba"), 4);
+    /// ```
+    pub fn solve_len(&mut self, text: &[u8]) -> usize {
+        let mut longest = 0;
+        search_with_successor(
+            &mut self.buffers,
+            text,
+            &u8::cmp,
+            |start, bound| successor_element(text, start, bound),
+            leftmost_distinct_characters(text),
+            &mut |stack: &[StackElement]| longest = longest.max(stack.len()),
+        );
+        longest
+    }
+}
+
+/// keeps the longest Lyndon subsequence according to the options of `solver`
+struct SolverObserver<'a, T, C> {
+    solver: &'a Solver<T, C>,
+    longest: Vec<StackElement>,
+    stats: SearchStats,
+}
+
+impl<T, C> Observer for SolverObserver<'_, T, C> {
+    fn lyndon(&mut self, stack: &[StackElement]) {
+        self.stats.lyndon_subsequences += 1;
+        let replace = match self.solver.tie_break {
+            TieBreak::First => self.longest.len() < stack.len(),
+            TieBreak::Last => self.longest.len() <= stack.len(),
+        };
+        if replace {
+            self.longest.clear();
+            self.longest.extend_from_slice(stack);
+        }
+    }
+
+    fn step(&mut self, _stack: &[StackElement]) -> ControlFlow<()> {
+        self.stats.steps += 1;
+        ControlFlow::Continue(())
+    }
+
+    fn max_len(&self) -> usize {
+        self.solver.max_len
+    }
+
+    fn prune(&self) -> bool {
+        self.solver.prune
+    }
+}
+
+#[test]
+fn test_solver() {
+    use crate::reference::all_words;
+    use crate::{iter_all_longest, longest_lyndon_subsequence, longest_lyndon_subsequence_bounded};
+
+    // all words of length 7 over {0, 1, 2}
+    for text in all_words(3, 7) {
+        assert_eq!(
+            Solver::new().solve(&text),
+            longest_lyndon_subsequence(&text)
+        );
+        assert_eq!(
+            Solver::new().max_len(3).solve(&text),
+            longest_lyndon_subsequence_bounded(&text, 3)
+        );
+
+        let all: Vec<Vec<u8>> = iter_all_longest(&text)
+            .map(|positions| positions.iter().map(|&i| text[i]).collect())
+            .collect();
+        let exhaustive = Solver::new().prune(false);
+        let (first, stats) = exhaustive.solve_with_stats(&text);
+        let last = exhaustive.tie_break(TieBreak::Last).solve(&text);
+        assert_eq!(first.subsequence(), all.iter().min().unwrap().as_slice());
+        assert_eq!(last.subsequence(), all.iter().max().unwrap().as_slice());
+        assert!(Solver::new().solve_with_stats(&text).1.steps <= stats.steps);
+    }
+}
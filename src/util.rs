@@ -0,0 +1,532 @@
+//! Building blocks of the search that are useful on their own, e.g., for subsequence automata.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::ops::{Bound, Range};
+
+/// returns the leftmost position `i >= start` holding the smallest character of `text[start..]` within `bound`,
+/// i.e., at least the bound if it is [`Bound::Included`], and larger than the bound if it is [`Bound::Excluded`]
+///
+/// ```
+/// use core::ops::{Bound, Range};
+/// use longestlyndonsubseq::util::successor_element;
+///
+/// assert_eq!(successor_element(b"bccadbacc", 1, Bound::Included(&b'b')), Some(5));
+/// assert_eq!(successor_element(b"bccadbacc", 1, Bound::Excluded(&b'c')), Some(4));
+/// assert_eq!(successor_element(b"bccadbacc", 5, Bound::Unbounded), Some(6));
+/// ```
+pub fn successor_element<T: Ord>(text: &[T], start: usize, bound: Bound<&T>) -> Option<usize> {
+    successor_element_by(text, &T::cmp, start, bound)
+}
+
+/// [`successor_element`] with respect to the alphabet order `cmp`
+pub fn successor_element_by<T>(
+    text: &[T],
+    cmp: &impl Fn(&T, &T) -> Ordering,
+    start: usize,
+    bound: Bound<&T>,
+) -> Option<usize> {
+    let mut successor: Option<usize> = None;
+    for (i, c) in text.iter().enumerate().skip(start) {
+        let order = match bound {
+            Bound::Included(value) | Bound::Excluded(value) => cmp(c, value),
+            Bound::Unbounded => Ordering::Greater,
+        };
+        if order == Ordering::Less
+            || (order == Ordering::Equal && matches!(bound, Bound::Excluded(_)))
+        {
+            continue;
+        }
+        if successor.is_none_or(|s| cmp(c, &text[s]) == Ordering::Less) {
+            successor = Some(i);
+            if order == Ordering::Equal {
+                // nothing smaller can follow
+                break;
+            }
+        }
+    }
+    successor
+}
+
+/// answers the successor queries of the search, i.e., [`successor_element`] queries on a fixed text
+///
+/// Implemented by the text itself (scanning it in `O(n)` time per query, without extra space),
+/// by [`NextOccurrenceIndex`] (`O(σ)` time, `O(nσ)` space),
+/// and by [`WaveletTree`] (`O(log n log σ)` time, `O(n log σ)` bits of space),
+/// see [`longest_lyndon_subsequence_with_index`](crate::longest_lyndon_subsequence_with_index).
+pub trait SuccessorIndex<T> {
+    /// same as [`successor_element`] on the indexed text restricted to the positions in `range`
+    fn successor_in(&self, range: Range<usize>, bound: Bound<&T>) -> Option<usize>;
+
+    /// same as [`successor_element`] on the indexed text
+    fn successor(&self, start: usize, bound: Bound<&T>) -> Option<usize> {
+        self.successor_in(start..usize::MAX, bound)
+    }
+}
+
+impl<T: Ord> SuccessorIndex<T> for [T] {
+    fn successor_in(&self, range: Range<usize>, bound: Bound<&T>) -> Option<usize> {
+        successor_element(&self[..range.end.min(self.len())], range.start, bound)
+    }
+}
+
+/// returns the leftmost occurrence of each distinct character, sorted by character
+///
+/// ```
+/// use longestlyndonsubseq::util::leftmost_distinct_characters;
+///
+/// assert!(leftmost_distinct_characters(b"bccadbacc").eq([3, 0, 1, 4]));
+/// ```
+pub fn leftmost_distinct_characters(text: &[u8]) -> impl Iterator<Item = usize> {
+    let mut charmap = [usize::MAX; u8::MAX as usize + 1];
+    for (i, c) in text.iter().enumerate().rev() {
+        charmap[*c as usize] = i;
+    }
+    // indexed by character, so already sorted by it, and without allocating
+    charmap.into_iter().filter(|&x| x != usize::MAX)
+}
+
+/// generic counterpart of [`leftmost_distinct_characters`] for arbitrary ordered alphabets
+pub fn leftmost_distinct_symbols<T: Ord>(text: &[T]) -> Vec<usize> {
+    leftmost_distinct_symbols_by(text, &T::cmp)
+}
+
+/// [`leftmost_distinct_symbols`] with respect to the alphabet order `cmp`
+pub fn leftmost_distinct_symbols_by<T>(
+    text: &[T],
+    cmp: &impl Fn(&T, &T) -> Ordering,
+) -> Vec<usize> {
+    let mut positions: Vec<usize> = (0..text.len()).collect();
+    // stable sort: among equal symbols the leftmost position comes first
+    positions.sort_by(|&a, &b| cmp(&text[a], &text[b]));
+    positions.dedup_by(|a, b| cmp(&text[*a], &text[*b]) == Ordering::Equal);
+    positions
+}
+
+/// next-occurrence table of a text answering [`successor_element`] queries in `O(σ)` time,
+/// where `σ` is the number of distinct characters of the text
+///
+/// The table takes `O(nσ)` words of space for a text of length `n`.
+///
+/// ```
+/// use core::ops::{Bound, Range};
+/// use longestlyndonsubseq::util::NextOccurrenceIndex;
+///
+/// let index = NextOccurrenceIndex::new(b"bccadbacc");
+/// assert_eq!(index.alphabet(), b"abcd");
+/// assert_eq!(index.next_occurrence(1, &b'b'), Some(5));
+/// assert_eq!(index.successor(1, Bound::Excluded(&b'c')), Some(4));
+/// assert!(index.leftmost_distinct(5).eq([6, 5, 7]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct NextOccurrenceIndex<T> {
+    /// the distinct characters of the text in ascending order
+    alphabet: Vec<T>,
+    /// `next[i * σ + r]` is the leftmost position `>= i` of the character with rank `r`, or `usize::MAX`
+    next: Vec<usize>,
+}
+
+impl<T: Ord + Copy> NextOccurrenceIndex<T> {
+    /// builds the table for `text`
+    pub fn new(text: &[T]) -> Self {
+        let alphabet: Vec<T> = leftmost_distinct_symbols(text)
+            .into_iter()
+            .map(|pos| text[pos])
+            .collect();
+        let sigma = alphabet.len();
+        let mut next = vec![usize::MAX; (text.len() + 1) * sigma];
+        for (i, c) in text.iter().enumerate().rev() {
+            next.copy_within((i + 1) * sigma..(i + 2) * sigma, i * sigma);
+            let rank = alphabet.binary_search(c).unwrap();
+            next[i * sigma + rank] = i;
+        }
+        NextOccurrenceIndex { alphabet, next }
+    }
+
+    /// the distinct characters of the text in ascending order
+    pub fn alphabet(&self) -> &[T] {
+        &self.alphabet
+    }
+
+    /// length of the indexed text
+    pub fn text_len(&self) -> usize {
+        if self.alphabet.is_empty() {
+            0
+        } else {
+            self.next.len() / self.alphabet.len() - 1
+        }
+    }
+
+    /// the next-occurrence row of position `start`, or `None` if `start` is out of range
+    fn row(&self, start: usize) -> Option<&[usize]> {
+        let sigma = self.alphabet.len();
+        (start < self.text_len()).then(|| &self.next[start * sigma..(start + 1) * sigma])
+    }
+
+    /// returns the leftmost position `i >= start` with `text[i] == c`
+    pub fn next_occurrence(&self, start: usize, c: &T) -> Option<usize> {
+        let rank = self.alphabet.binary_search(c).ok()?;
+        let pos = self.row(start)?[rank];
+        (pos != usize::MAX).then_some(pos)
+    }
+
+    /// same as [`successor_element`] on the indexed text
+    pub fn successor(&self, start: usize, bound: Bound<&T>) -> Option<usize> {
+        self.successor_in(start..usize::MAX, bound)
+    }
+
+    /// same as [`successor_element`] on the indexed text restricted to the positions in `range`
+    pub fn successor_in(&self, range: Range<usize>, bound: Bound<&T>) -> Option<usize> {
+        let first_rank = match bound {
+            Bound::Included(value) => self.alphabet.partition_point(|c| c < value),
+            Bound::Excluded(value) => self.alphabet.partition_point(|c| c <= value),
+            Bound::Unbounded => 0,
+        };
+        self.row(range.start)?[first_rank..]
+            .iter()
+            .copied()
+            .find(|&pos| pos < range.end)
+    }
+
+    /// returns the leftmost occurrence `>= start` of each distinct character of `text[start..]`, sorted by character
+    pub fn leftmost_distinct(&self, start: usize) -> impl Iterator<Item = usize> + '_ {
+        self.leftmost_distinct_in(start..usize::MAX)
+    }
+
+    /// returns the leftmost occurrence in `range` of each distinct character of the text in `range`, sorted by character
+    pub fn leftmost_distinct_in(&self, range: Range<usize>) -> impl Iterator<Item = usize> + '_ {
+        self.row(range.start)
+            .unwrap_or_default()
+            .iter()
+            .copied()
+            .filter(move |&pos| pos < range.end)
+    }
+}
+
+impl<T: Ord + Copy> SuccessorIndex<T> for NextOccurrenceIndex<T> {
+    fn successor_in(&self, range: Range<usize>, bound: Bound<&T>) -> Option<usize> {
+        NextOccurrenceIndex::successor_in(self, range, bound)
+    }
+}
+
+/// bit vector with constant-time rank queries
+#[derive(Debug, Clone)]
+struct BitVector {
+    words: Vec<u64>,
+    /// `ranks[w]` is the number of set bits in the words before `w`
+    ranks: Vec<usize>,
+}
+
+impl BitVector {
+    fn new(bits: impl ExactSizeIterator<Item = bool>) -> Self {
+        let mut words = vec![0u64; bits.len().div_ceil(64)];
+        for (i, bit) in bits.enumerate() {
+            words[i / 64] |= (bit as u64) << (i % 64);
+        }
+        let mut ranks = Vec::with_capacity(words.len() + 1);
+        ranks.push(0);
+        for word in &words {
+            ranks.push(ranks.last().unwrap() + word.count_ones() as usize);
+        }
+        BitVector { words, ranks }
+    }
+
+    fn get(&self, i: usize) -> bool {
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    /// number of set bits before position `i`
+    fn rank1(&self, i: usize) -> usize {
+        let mask = (1u64 << (i % 64)).wrapping_sub(1);
+        self.ranks[i / 64]
+            + self
+                .words
+                .get(i / 64)
+                .map_or(0, |w| (w & mask).count_ones() as usize)
+    }
+
+    /// number of unset bits before position `i`
+    fn rank0(&self, i: usize) -> usize {
+        i - self.rank1(i)
+    }
+
+    /// position of the `k`-th (0-based) bit equal to `bit`
+    fn select(&self, bit: bool, k: usize) -> usize {
+        let rank = |i| if bit { self.rank1(i) } else { self.rank0(i) };
+        // smallest i with rank(i + 1) > k
+        let (mut lo, mut hi) = (0, self.words.len() * 64);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if rank(mid + 1) > k {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        lo
+    }
+}
+
+/// wavelet tree (in its level-wise layout, the wavelet matrix) of a text,
+/// answering [`successor_element`] queries in `O(log n log σ)` time
+///
+/// Besides the alphabet, the tree takes `n ⌈log σ⌉` bits plus lower-order terms of space for a text of length `n`
+/// with `σ` distinct characters.
+///
+/// ```
+/// use core::ops::Bound;
+/// use longestlyndonsubseq::util::{SuccessorIndex, WaveletTree};
+///
+/// let tree = WaveletTree::new(b"bccadbacc");
+/// assert_eq!(tree.successor(1, Bound::Included(&b'b')), Some(5));
+/// assert_eq!(tree.successor_in(1..4, Bound::Excluded(&b'b')), Some(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct WaveletTree<T> {
+    /// the distinct characters of the text in ascending order
+    alphabet: Vec<T>,
+    /// one bit vector per bit of the character ranks, most significant bit first
+    levels: Vec<BitVector>,
+    /// number of unset bits of each level
+    zeros: Vec<usize>,
+    len: usize,
+}
+
+impl<T: Ord + Copy> WaveletTree<T> {
+    /// builds the tree for `text`
+    pub fn new(text: &[T]) -> Self {
+        let alphabet: Vec<T> = leftmost_distinct_symbols(text)
+            .into_iter()
+            .map(|pos| text[pos])
+            .collect();
+        let height = (usize::BITS - alphabet.len().saturating_sub(1).leading_zeros()) as usize;
+        let mut ranks: Vec<usize> = text
+            .iter()
+            .map(|c| alphabet.binary_search(c).unwrap())
+            .collect();
+        let mut levels = Vec::with_capacity(height);
+        let mut zeros = Vec::with_capacity(height);
+        for level in 0..height {
+            let shift = height - 1 - level;
+            let bits = BitVector::new(ranks.iter().map(|r| r >> shift & 1 == 1));
+            zeros.push(bits.rank0(ranks.len()));
+            levels.push(bits);
+            // stable partition by the current bit
+            ranks = ranks
+                .iter()
+                .filter(|&r| r >> shift & 1 == 0)
+                .chain(ranks.iter().filter(|&r| r >> shift & 1 == 1))
+                .copied()
+                .collect();
+        }
+        WaveletTree {
+            alphabet,
+            levels,
+            zeros,
+            len: text.len(),
+        }
+    }
+
+    /// the distinct characters of the text in ascending order
+    pub fn alphabet(&self) -> &[T] {
+        &self.alphabet
+    }
+
+    /// length of the indexed text
+    pub fn text_len(&self) -> usize {
+        self.len
+    }
+
+    /// the character at position `i`
+    pub fn get(&self, i: usize) -> T {
+        let mut rank = 0;
+        let mut pos = i;
+        for (level, bits) in self.levels.iter().enumerate() {
+            let bit = bits.get(pos);
+            rank = rank << 1 | bit as usize;
+            pos = self.child(level, bit, pos);
+        }
+        self.alphabet[rank]
+    }
+
+    /// maps position `pos` of `level` to the next level, following `bit`
+    fn child(&self, level: usize, bit: bool, pos: usize) -> usize {
+        if bit {
+            self.zeros[level] + self.levels[level].rank1(pos)
+        } else {
+            self.levels[level].rank0(pos)
+        }
+    }
+
+    /// smallest rank `>= lower` occurring in the positions `start..end` of `level`, where the ranks
+    /// of these positions share the prefix `prefix` of the more significant bits
+    fn next_rank(
+        &self,
+        level: usize,
+        start: usize,
+        end: usize,
+        prefix: usize,
+        lower: usize,
+    ) -> Option<usize> {
+        if start >= end {
+            return None;
+        }
+        let height = self.levels.len();
+        if level == height {
+            return (prefix >= lower).then_some(prefix);
+        }
+        let shift = height - 1 - level;
+        for bit in [false, true] {
+            let child_prefix = prefix << 1 | bit as usize;
+            // all ranks of the child are smaller than `lower`
+            if (child_prefix + 1) << shift <= lower {
+                continue;
+            }
+            let rank = self.next_rank(
+                level + 1,
+                self.child(level, bit, start),
+                self.child(level, bit, end),
+                child_prefix,
+                lower,
+            );
+            if rank.is_some() {
+                return rank;
+            }
+        }
+        None
+    }
+
+    /// maps the positions `start..end` of level 0 to the block of the last level holding the character with rank `rank`
+    fn descend(&self, rank: usize, mut start: usize, mut end: usize) -> (usize, usize) {
+        let height = self.levels.len();
+        for level in 0..height {
+            let bit = rank >> (height - 1 - level) & 1 == 1;
+            start = self.child(level, bit, start);
+            end = self.child(level, bit, end);
+        }
+        (start, end)
+    }
+
+    /// position of the `k`-th (0-based) occurrence of the character with rank `rank`
+    fn select(&self, rank: usize, k: usize) -> usize {
+        let height = self.levels.len();
+        let mut pos = self.descend(rank, 0, 0).0 + k;
+        for level in (0..height).rev() {
+            let bit = rank >> (height - 1 - level) & 1 == 1;
+            pos = if bit {
+                self.levels[level].select(true, pos - self.zeros[level])
+            } else {
+                self.levels[level].select(false, pos)
+            };
+        }
+        pos
+    }
+}
+
+impl<T: Ord + Copy> SuccessorIndex<T> for WaveletTree<T> {
+    fn successor_in(&self, range: Range<usize>, bound: Bound<&T>) -> Option<usize> {
+        let lower = match bound {
+            Bound::Included(value) => self.alphabet.partition_point(|c| c < value),
+            Bound::Excluded(value) => self.alphabet.partition_point(|c| c <= value),
+            Bound::Unbounded => 0,
+        };
+        let end = range.end.min(self.len);
+        if range.start >= end {
+            return None;
+        }
+        let rank = self.next_rank(0, range.start, end, 0, lower)?;
+        let (block_start, before_start) = self.descend(rank, 0, range.start);
+        Some(self.select(rank, before_start - block_start))
+    }
+}
+
+#[test]
+fn test_successor_element() {
+    let text = b"bccadbaccbcd";
+    for start in 0..=text.len() + 1 {
+        for value in b'a'..=b'e' {
+            for bound in [Bound::Included(&value), Bound::Excluded(&value)] {
+                let expected = (start..text.len())
+                    .filter(|&i| match bound {
+                        Bound::Included(v) => text[i] >= *v,
+                        Bound::Excluded(v) => text[i] > *v,
+                        Bound::Unbounded => true,
+                    })
+                    .min_by_key(|&i| (text[i], i));
+                assert_eq!(successor_element(text, start, bound), expected);
+            }
+        }
+    }
+    assert_eq!(successor_element(b"cba", 0, Bound::Unbounded), Some(2));
+    assert_eq!(successor_element::<u8>(b"", 0, Bound::Unbounded), None);
+}
+
+#[test]
+fn test_next_occurrence_index() {
+    let text = b"bccadbaccbcd";
+    let index = NextOccurrenceIndex::new(text);
+    assert_eq!(index.text_len(), text.len());
+    for start in 0..=text.len() + 1 {
+        for value in b'a'..=b'e' {
+            for bound in [
+                Bound::Included(&value),
+                Bound::Excluded(&value),
+                Bound::Unbounded,
+            ] {
+                assert_eq!(
+                    index.successor(start, bound),
+                    successor_element(text, start, bound)
+                );
+            }
+            assert_eq!(
+                index.next_occurrence(start, &value),
+                text.iter()
+                    .skip(start)
+                    .position(|&c| c == value)
+                    .map(|i| start + i)
+            );
+        }
+        let tail = text.get(start..).unwrap_or_default();
+        assert!(index
+            .leftmost_distinct(start)
+            .eq(leftmost_distinct_characters(tail).map(|i| start + i)));
+        assert!(leftmost_distinct_characters(tail).eq(leftmost_distinct_symbols(tail)));
+    }
+    assert_eq!(
+        NextOccurrenceIndex::<u8>::new(b"").successor(0, Bound::Unbounded),
+        None
+    );
+}
+
+#[test]
+fn test_wavelet_tree() {
+    for text in [
+        &b"bccadbaccbcd"[..],
+        b"",
+        b"aaa",
+        b"ab",
+        b"edcbaedcbaf",
+        b"abcdefgh",
+    ] {
+        let tree = WaveletTree::new(text);
+        assert_eq!(tree.text_len(), text.len());
+        assert!((0..text.len()).all(|i| tree.get(i) == text[i]));
+        for start in 0..=text.len() + 1 {
+            for end in start..=text.len() + 1 {
+                for value in b'a'..=b'i' {
+                    for bound in [
+                        Bound::Included(&value),
+                        Bound::Excluded(&value),
+                        Bound::Unbounded,
+                    ] {
+                        assert_eq!(
+                            tree.successor_in(start..end, bound),
+                            text.successor_in(start..end, bound)
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! JavaScript bindings via `wasm-bindgen`, see the README for building
+
+use alloc::vec::Vec;
+
+use wasm_bindgen::prelude::*;
+
+use crate::LyndonResult;
+
+/// a longest Lyndon subsequence, exported to JavaScript as `LyndonResult`
+#[wasm_bindgen(js_name = LyndonResult)]
+pub struct WasmLyndonResult(LyndonResult);
+
+#[wasm_bindgen(js_class = LyndonResult)]
+impl WasmLyndonResult {
+    /// length of the subsequence
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.0.len()
+    }
+
+    /// text positions of the subsequence as `Uint32Array`
+    #[wasm_bindgen(getter)]
+    pub fn positions(&self) -> Vec<usize> {
+        self.0.positions().to_vec()
+    }
+
+    /// characters of the subsequence as `Uint8Array`
+    #[wasm_bindgen(getter)]
+    pub fn subsequence(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+}
+
+/// computes a longest Lyndon subsequence of the bytes in the `Uint8Array` `text`
+#[wasm_bindgen(js_name = longestLyndonSubsequence)]
+pub fn longest_lyndon_subsequence(text: &[u8]) -> WasmLyndonResult {
+    WasmLyndonResult(crate::longest_lyndon_subsequence_bytes(text))
+}